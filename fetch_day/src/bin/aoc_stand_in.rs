@@ -0,0 +1,357 @@
+//! Stand-in for adventofcode.com, serving recorded pages from a fixtures
+//! directory so fetch_day can be run and tested offline:
+//!
+//! ```text
+//! cargo run -p fetch_day --bin aoc_stand_in -- --session abc123
+//! cargo run -p fetch_day -- --base-url http://127.0.0.1:<port> --year 2023 --day 1
+//! ```
+//!
+//! The fixtures directory is laid out as:
+//!
+//! ```text
+//! account/logged_in.html, logged_out.html    /settings
+//! answer/<verdict>.html                      responses to a posted answer
+//! calendar/<year>.html                       /<year>
+//! input/locked.txt, logged_out.txt           error pages
+//! leaderboard/<year>_<id>.json               /<year>/leaderboard/private/view/<id>.json
+//! puzzles/<year>/day_05/puzzle.html          /<year>/day/5
+//! puzzles/<year>/day_05/input.txt            /<year>/day/5/input
+//! puzzles/<year>/day_05/answers.txt          the answer to each part, one per line
+//! ```
+
+use std::{
+    collections::HashSet,
+    fs,
+    io::{BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+
+#[path = "../http_request.rs"]
+mod http_request;
+
+use http_request::Request;
+
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(
+        long,
+        value_name = "DIR",
+        default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"),
+        help = "Directory of recorded pages to serve"
+    )]
+    fixtures: PathBuf,
+
+    #[arg(
+        long,
+        env = "AOC_SESSION_TOKEN",
+        help = "Session token a request must carry to be logged in"
+    )]
+    session: String,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Port to listen on, or 0 for any free port"
+    )]
+    port: u16,
+
+    #[arg(
+        long,
+        value_name = "DAY",
+        default_value_t = 25,
+        help = "Last day which has unlocked; later days are served as locked"
+    )]
+    unlocked_through: u32,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value = "0",
+        value_parser = parse_seconds,
+        help = "Refuse requests sooner than this after the previous one with 429 Too Many Requests"
+    )]
+    min_interval: Duration,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value = "60",
+        value_parser = parse_seconds,
+        help = "How long answers are rate limited after a wrong one"
+    )]
+    answer_cooldown: Duration,
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    s.parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| format!("'{s}' is not a number of seconds"))
+}
+
+fn main() -> std::io::Result<()> {
+    let opts = Opts::parse();
+    let listener = TcpListener::bind(("127.0.0.1", opts.port))?;
+
+    // Callers wait for this line to learn the port
+    println!("Listening on http://{}", listener.local_addr()?);
+    std::io::stdout().flush()?;
+
+    let mut stand_in = StandIn::new(opts);
+    for stream in listener.incoming() {
+        if let Err(err) = stream.and_then(|stream| stand_in.serve(stream)) {
+            eprintln!("Error: {err}");
+        }
+    }
+    Ok(())
+}
+
+impl Request {
+    /// The value of the cookie `name`, e.g. `session`.
+    fn cookie(&self, name: &str) -> Option<&str> {
+        self.header("cookie")?
+            .split(';')
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v)
+    }
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn text(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain",
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn write(&self, stream: &mut impl Write) -> std::io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            429 => "Too Many Requests",
+            _ => "",
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            self.content_type,
+            self.body.len()
+        )?;
+        stream.write_all(&self.body)?;
+        stream.flush()
+    }
+}
+
+struct StandIn {
+    opts: Opts,
+    last_request: Option<Instant>,
+    last_wrong_answer: Option<Instant>,
+    /// The `(year, day, part)`s answered correctly so far.
+    solved: HashSet<(u32, u32, u32)>,
+}
+
+impl StandIn {
+    fn new(opts: Opts) -> Self {
+        Self {
+            opts,
+            last_request: None,
+            last_wrong_answer: None,
+            solved: HashSet::new(),
+        }
+    }
+
+    fn serve(&mut self, stream: TcpStream) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream);
+        let request = Request::read(&mut reader)?;
+        let response = self.respond(&request);
+        eprintln!("{} {} -> {}", request.method, request.path, response.status);
+        response.write(reader.get_mut())
+    }
+
+    fn respond(&mut self, request: &Request) -> Response {
+        let now = Instant::now();
+        let too_soon = self
+            .last_request
+            .is_some_and(|last| now - last < self.opts.min_interval);
+        self.last_request = Some(now);
+        if too_soon {
+            return Response::text(429, "Too Many Requests");
+        }
+
+        let logged_in = request.cookie("session") == Some(self.opts.session.as_str());
+        let segments = request
+            .path
+            .trim_matches('/')
+            .split('/')
+            .collect::<Vec<_>>();
+
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["settings"]) if logged_in => self.fixture(200, "account/logged_in.html"),
+            ("GET", ["settings"]) => self.fixture(200, "account/logged_out.html"),
+            ("GET", [year, "leaderboard", "private", "view", board]) => {
+                match board.strip_suffix(".json") {
+                    Some(_) if !logged_in => self.fixture(400, "input/logged_out.txt"),
+                    Some(id) => self.fixture(200, &format!("leaderboard/{year}_{id}.json")),
+                    None => not_found(&request.path),
+                }
+            }
+            (method, [year, "day", day, rest @ ..]) => {
+                let (Ok(year), Ok(day)) = (year.parse(), day.parse()) else {
+                    return not_found(&request.path);
+                };
+                if day > self.opts.unlocked_through {
+                    return self.fixture(404, "input/locked.txt");
+                }
+                match (method, rest) {
+                    ("GET", []) => self.fixture(200, &puzzle_file(year, day, "puzzle.html")),
+                    ("GET", ["input"]) if logged_in => {
+                        self.fixture(200, &puzzle_file(year, day, "input.txt"))
+                    }
+                    ("POST", ["answer"]) if logged_in => self.answer(year, day, &request.body),
+                    ("GET", ["input"]) | ("POST", ["answer"]) => {
+                        self.fixture(400, "input/logged_out.txt")
+                    }
+                    _ => not_found(&request.path),
+                }
+            }
+            ("GET", [year]) => self.fixture(200, &format!("calendar/{year}.html")),
+            ("GET", _) => not_found(&request.path),
+            _ => Response::text(405, "Method Not Allowed"),
+        }
+    }
+
+    /// Judges a posted `level=1&answer=42` form against `answers.txt`.
+    fn answer(&mut self, year: u32, day: u32, form: &str) -> Response {
+        let fields = parse_form(form);
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+        let (Some(Ok(part)), Some(answer)) =
+            (field("level").map(str::parse::<u32>), field("answer"))
+        else {
+            return Response::text(400, "Expected 'level' and 'answer' fields");
+        };
+
+        if self.solved.contains(&(year, day, part)) {
+            return self.fixture(200, "answer/already_solved.html");
+        }
+        if self
+            .last_wrong_answer
+            .is_some_and(|at| at.elapsed() < self.opts.answer_cooldown)
+        {
+            return self.fixture(200, "answer/rate_limited.html");
+        }
+
+        let answers_path = puzzle_file(year, day, "answers.txt");
+        let Ok(answers) = fs::read_to_string(self.opts.fixtures.join(&answers_path)) else {
+            return not_found(&answers_path);
+        };
+        let Some(expected) = answers.lines().nth(part.saturating_sub(1) as usize) else {
+            return not_found(&format!("{answers_path} line {part}"));
+        };
+
+        let verdict = if answer == expected.trim() {
+            self.solved.insert((year, day, part));
+            "correct"
+        } else {
+            self.last_wrong_answer = Some(Instant::now());
+            match (answer.parse::<i64>(), expected.trim().parse::<i64>()) {
+                (Ok(answer), Ok(expected)) if answer > expected => "too_high",
+                (Ok(answer), Ok(expected)) if answer < expected => "too_low",
+                _ => "incorrect",
+            }
+        };
+        self.fixture(200, &format!("answer/{verdict}.html"))
+    }
+
+    fn fixture(&self, status: u16, path: &str) -> Response {
+        let Ok(body) = fs::read(self.opts.fixtures.join(path)) else {
+            return not_found(path);
+        };
+        let content_type = match path.rsplit_once('.') {
+            Some((_, "json")) => "application/json",
+            Some((_, "txt")) => "text/plain",
+            _ => "text/html",
+        };
+        Response {
+            status,
+            content_type,
+            body,
+        }
+    }
+}
+
+/// `puzzles/2023/day_05/<name>`
+fn puzzle_file(year: u32, day: u32, name: &str) -> String {
+    format!("puzzles/{year}/day_{day:02}/{name}")
+}
+
+fn not_found(what: &str) -> Response {
+    Response::text(404, &format!("Nothing recorded for {what}"))
+}
+
+/// Decodes an `application/x-www-form-urlencoded` body.
+fn parse_form(form: &str) -> Vec<(String, String)> {
+    form.split('&')
+        .filter_map(|field| field.split_once('='))
+        .map(|(k, v)| (url_decode(k), url_decode(v)))
+        .collect()
+}
+
+fn url_decode(s: &str) -> String {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => match rest
+                .get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(decoded) => {
+                    bytes.push(decoded);
+                    rest = &rest[2..];
+                }
+                None => bytes.push(b'%'),
+            },
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answer_form() {
+        assert_eq!(
+            vec![
+                ("level".to_string(), "2".to_string()),
+                ("answer".to_string(), "a b&c%".to_string()),
+            ],
+            parse_form("level=2&answer=a+b%26c%")
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use std::{fmt::Display, io::Write, time::Duration};
+
+use chrono::{DateTime, Datelike, FixedOffset, TimeZone, Utc};
+
+use crate::error::{Error, WithPath};
+
+/// Puzzles unlock at midnight in UTC-5 (EST, as December has no daylight saving).
+const UNLOCK_OFFSET_SECS: i32 = 5 * 3600;
+
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// A puzzle which was requested before it unlocked.
+#[derive(Debug, PartialEq, Eq)]
+pub struct NotUnlocked {
+    pub year: u32,
+    pub day: u32,
+    pub remaining: Duration,
+}
+
+impl Display for NotUnlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} day {} unlocks in {}; use '--wait' to fetch it when it does",
+            self.year,
+            self.day,
+            format_duration(self.remaining)
+        )
+    }
+}
+
+impl std::error::Error for NotUnlocked {}
+
+fn aoc_timezone() -> FixedOffset {
+    FixedOffset::west_opt(UNLOCK_OFFSET_SECS).unwrap()
+}
+
+/// The instant the given puzzle unlocks, or an error if December of `year`
+/// has no such day.
+pub fn unlock_time(year: u32, day: u32) -> Result<DateTime<Utc>, Error> {
+    i32::try_from(year)
+        .ok()
+        .and_then(|year| {
+            aoc_timezone()
+                .with_ymd_and_hms(year, 12, day, 0, 0, 0)
+                .single()
+        })
+        .map(|unlock| unlock.with_timezone(&Utc))
+        .ok_or_else(|| Error::Usage(format!("there's no day {day} in December {year}")))
+}
+
+/// Current day of the month in the AoC timezone, capped at 25.
+pub fn default_day(clock: &impl Clock) -> u32 {
+    clock.now().with_timezone(&aoc_timezone()).day().min(25)
+}
+
+/// Time left until the puzzle unlocks, or `None` if it already has.
+pub fn time_until_unlock(
+    clock: &impl Clock,
+    year: u32,
+    day: u32,
+) -> Result<Option<Duration>, Error> {
+    Ok((unlock_time(year, day)? - clock.now())
+        .to_std()
+        .ok()
+        .filter(|remaining| !remaining.is_zero()))
+}
+
+/// Refuses puzzles which haven't unlocked yet, so the request never reaches AoC.
+pub fn ensure_unlocked(clock: &impl Clock, year: u32, day: u32) -> Result<(), Error> {
+    match time_until_unlock(clock, year, day)? {
+        Some(remaining) => Err(Error::NotUnlocked(NotUnlocked {
+            year,
+            day,
+            remaining,
+        })),
+        None => Ok(()),
+    }
+}
+
+/// Sleeps until the puzzle unlocks plus `jitter`, writing a countdown to
+/// stdout, or `out` in tests, every second.
+pub fn wait_for_unlock(
+    clock: &impl Clock,
+    year: u32,
+    day: u32,
+    jitter: Duration,
+    out: &mut impl Write,
+) -> Result<(), Error> {
+    while let Some(remaining) = time_until_unlock(clock, year, day)? {
+        write!(
+            out,
+            "\r{year} day {day} unlocks in {}",
+            format_duration(remaining)
+        )
+        .and_then(|()| out.flush())
+        .with_path("stdout")?;
+        clock.sleep(remaining.min(Duration::from_secs(1)));
+    }
+    writeln!(out, "\r{year} day {day} unlocked{:20}", "").with_path("stdout")?;
+
+    clock.sleep(jitter);
+    Ok(())
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if days > 0 {
+        format!("{days}d {hours:02}:{mins:02}:{secs:02}")
+    } else {
+        format!("{hours:02}:{mins:02}:{secs:02}")
+    }
+}
+
+/// Clock for tests, which only moves when slept on.
+#[cfg(test)]
+pub mod manual {
+    use std::{
+        cell::{Cell, RefCell},
+        time::Duration,
+    };
+
+    use chrono::{DateTime, Utc};
+
+    use super::Clock;
+
+    pub struct ManualClock {
+        now: Cell<DateTime<Utc>>,
+        pub sleeps: RefCell<Vec<Duration>>,
+    }
+
+    impl ManualClock {
+        pub fn at(rfc3339: &str) -> Self {
+            Self {
+                now: Cell::new(DateTime::parse_from_rfc3339(rfc3339).unwrap().into()),
+                sleeps: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.now.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.now
+                .set(self.now.get() + chrono::Duration::from_std(duration).unwrap());
+            self.sleeps.borrow_mut().push(duration);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{manual::ManualClock, *};
+
+    #[test]
+    fn unlock_time_is_midnight_est() {
+        assert_eq!(
+            DateTime::parse_from_rfc3339("2023-12-05T05:00:00Z").unwrap(),
+            unlock_time(2023, 5).unwrap()
+        )
+    }
+
+    #[test]
+    fn unlock_time_of_no_day() {
+        for (year, day) in [(2023, 0), (2023, 32), (u32::MAX, 5)] {
+            assert!(
+                matches!(unlock_time(year, day), Err(Error::Usage(_))),
+                "{year} day {day}"
+            );
+        }
+    }
+
+    mod default_day {
+        use super::*;
+
+        struct Case {
+            now: &'static str,
+            expected: u32,
+        }
+
+        #[test]
+        fn before_midnight_est() {
+            run(&Case {
+                now: "2023-12-05T04:59:59Z",
+                expected: 4,
+            })
+        }
+
+        #[test]
+        fn after_midnight_est() {
+            run(&Case {
+                now: "2023-12-05T05:00:00Z",
+                expected: 5,
+            })
+        }
+
+        #[test]
+        fn after_christmas() {
+            run(&Case {
+                now: "2023-12-30T12:00:00Z",
+                expected: 25,
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, default_day(&ManualClock::at(test.now)))
+        }
+    }
+
+    mod ensure_unlocked {
+        use super::*;
+
+        struct Case {
+            now: &'static str,
+            expected: Result<(), Duration>,
+        }
+
+        #[test]
+        fn unlocked() {
+            run(&Case {
+                now: "2023-12-05T05:00:00Z",
+                expected: Ok(()),
+            })
+        }
+
+        #[test]
+        fn locked() {
+            run(&Case {
+                now: "2023-12-05T04:58:30Z",
+                expected: Err(Duration::from_secs(90)),
+            })
+        }
+
+        fn run(test: &Case) {
+            let actual =
+                ensure_unlocked(&ManualClock::at(test.now), 2023, 5).map_err(|err| match err {
+                    Error::NotUnlocked(err) => err,
+                    err => panic!("expected NotUnlocked, got {err:?}"),
+                });
+            assert_eq!(
+                test.expected.map_err(|remaining| NotUnlocked {
+                    year: 2023,
+                    day: 5,
+                    remaining
+                }),
+                actual
+            )
+        }
+    }
+
+    #[test]
+    fn wait_for_unlock_counts_down_then_jitters() {
+        let clock = ManualClock::at("2023-12-05T04:59:57.500Z");
+        let mut out = Vec::new();
+
+        wait_for_unlock(&clock, 2023, 5, Duration::from_millis(250), &mut out).unwrap();
+
+        assert_eq!(
+            vec![
+                Duration::from_secs(1),
+                Duration::from_secs(1),
+                Duration::from_millis(500),
+                Duration::from_millis(250),
+            ],
+            *clock.sleeps.borrow()
+        );
+        assert_eq!(
+            DateTime::parse_from_rfc3339("2023-12-05T05:00:00.250Z").unwrap(),
+            clock.now()
+        );
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("\r2023 day 5 unlocks in 00:00:03\r2023 day 5 unlocks in 00:00:02"));
+    }
+
+    #[test]
+    fn format_duration_with_days() {
+        assert_eq!(
+            "1d 02:03:05",
+            format_duration(
+                Duration::from_secs(86_400 + 2 * 3600 + 3 * 60 + 4) + Duration::from_millis(1)
+            )
+        )
+    }
+}
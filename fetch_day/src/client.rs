@@ -0,0 +1,322 @@
+use std::{
+    cell::Cell,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use reqwest::{
+    blocking::{Client, Response},
+    StatusCode,
+};
+
+pub const AOC_URL: &str = "https://adventofcode.com";
+
+/// Identifies the tool to AoC, as its automation guidelines ask.
+pub const USER_AGENT: &str = concat!(
+    "fetch_day/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/maneac/aoc2023)"
+);
+
+/// A request to AoC which didn't return the page that was asked for.
+#[derive(Debug)]
+pub enum FetchError {
+    Request(reqwest::Error),
+    /// The session cookie is missing or has expired.
+    LoggedOut {
+        url: String,
+    },
+    /// The puzzle hasn't unlocked yet.
+    Locked {
+        url: String,
+    },
+    Status {
+        url: String,
+        status: StatusCode,
+    },
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::Request(err) => write!(f, "request failed: {err}"),
+            FetchError::LoggedOut { url } => write!(
+                f,
+                "not logged in when requesting {url}: check 'AOC_SESSION_TOKEN' hasn't expired"
+            ),
+            FetchError::Locked { url } => write!(f, "{url} hasn't unlocked yet"),
+            FetchError::Status { url, status } => write!(f, "{url} returned {status}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FetchError::Request(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(err: reqwest::Error) -> Self {
+        FetchError::Request(err)
+    }
+}
+
+/// HTTP client which authenticates every request with the AoC session cookie,
+/// spacing requests at least `min_interval` apart.
+pub struct AocClient {
+    client: Client,
+    host: String,
+    token: String,
+    min_interval: Duration,
+    last_request: Cell<Option<Instant>>,
+}
+
+impl AocClient {
+    pub fn new(host: impl Into<String>, token: impl Into<String>) -> Self {
+        Self::with_user_agent(host, token, USER_AGENT)
+    }
+
+    fn with_user_agent(
+        host: impl Into<String>,
+        token: impl Into<String>,
+        user_agent: &str,
+    ) -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent(user_agent)
+                .build()
+                .expect("HTTP client failed to initialise"),
+            host: host.into(),
+            token: token.into(),
+            min_interval: Duration::ZERO,
+            last_request: Cell::new(None),
+        }
+    }
+
+    /// Adds `contact`, e.g. an email address, to the User-Agent so AoC can
+    /// get in touch about the traffic.
+    pub fn with_contact(self, contact: &str) -> Self {
+        Self {
+            min_interval: self.min_interval,
+            ..Self::with_user_agent(
+                self.host,
+                self.token,
+                &format!("{}; {contact})", USER_AGENT.trim_end_matches(')')),
+            )
+        }
+    }
+
+    pub fn with_min_interval(self, min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            ..self
+        }
+    }
+
+    /// Builds an absolute URL for a path on the AoC host, e.g. `/2023/day/5`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.host.trim_end_matches('/'), path)
+    }
+
+    pub fn get(&self, url: &str) -> Result<String, FetchError> {
+        let req = self
+            .client
+            .get(url)
+            .header("Cookie", format!("session={}", self.token))
+            .build()?;
+
+        validate(url, self.execute(req)?)
+    }
+
+    pub fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<String, FetchError> {
+        let req = self
+            .client
+            .post(url)
+            .header("Cookie", format!("session={}", self.token))
+            .form(form)
+            .build()?;
+
+        validate(url, self.execute(req)?)
+    }
+
+    fn execute(&self, req: reqwest::blocking::Request) -> Result<Response, FetchError> {
+        if let Some(last) = self.last_request.get() {
+            if let Some(wait) = self.min_interval.checked_sub(last.elapsed()) {
+                std::thread::sleep(wait);
+            }
+        }
+        let response = self.client.execute(req);
+        self.last_request.set(Some(Instant::now()));
+        Ok(response?)
+    }
+}
+
+/// Turns error pages into a [`FetchError`] so they are never mistaken for puzzle content.
+fn validate(url: &str, response: Response) -> Result<String, FetchError> {
+    let status = response.status();
+    let body = response.text()?;
+
+    if body.contains("Please log in") {
+        return Err(FetchError::LoggedOut {
+            url: url.to_string(),
+        });
+    }
+    if body.contains("before it unlocks") {
+        return Err(FetchError::Locked {
+            url: url.to_string(),
+        });
+    }
+    if !status.is_success() {
+        return Err(FetchError::Status {
+            url: url.to_string(),
+            status,
+        });
+    }
+
+    Ok(body)
+}
+
+/// Minimal single-shot HTTP server standing in for adventofcode.com in tests.
+#[cfg(test)]
+pub mod stand_in {
+    use std::{
+        io::{BufReader, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    pub use crate::http_request::Request;
+
+    /// Serves `body` with `status` to the first connection, returning the
+    /// host URL and a handle yielding the request that was received.
+    pub fn serve_once(status: u16, body: &'static str) -> (String, JoinHandle<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let host = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let request = Request::read(&mut reader).unwrap();
+
+            let mut stream = reader.into_inner();
+            let reason = reqwest::StatusCode::from_u16(status)
+                .ok()
+                .and_then(|status| status.canonical_reason())
+                .unwrap_or("");
+            write!(
+                stream,
+                "HTTP/1.1 {status} {reason}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+
+            request
+        });
+
+        (host, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod get {
+        use super::*;
+
+        struct Case {
+            status: u16,
+            body: &'static str,
+            expected: Result<&'static str, &'static str>,
+        }
+
+        #[test]
+        fn ok() {
+            run(&Case {
+                status: 200,
+                body: "1 2 3\n",
+                expected: Ok("1 2 3\n"),
+            })
+        }
+
+        #[test]
+        fn logged_out() {
+            run(&Case {
+                status: 400,
+                body: include_str!("../fixtures/input/logged_out.txt"),
+                expected: Err("LoggedOut"),
+            })
+        }
+
+        #[test]
+        fn locked() {
+            run(&Case {
+                status: 404,
+                body: include_str!("../fixtures/input/locked.txt"),
+                expected: Err("Locked"),
+            })
+        }
+
+        #[test]
+        fn server_error() {
+            run(&Case {
+                status: 500,
+                body: include_str!("../fixtures/input/bad_session.txt"),
+                expected: Err("Status"),
+            })
+        }
+
+        fn run(test: &Case) {
+            let (host, server) = stand_in::serve_once(test.status, test.body);
+            let client = AocClient::new(host, "token");
+
+            let actual = client.get(&client.url("/2023/day/5/input"));
+            server.join().unwrap();
+
+            match (test.expected, actual) {
+                (Ok(expected), Ok(actual)) => assert_eq!(expected, actual),
+                (Err(expected), Err(actual)) => {
+                    assert!(format!("{actual:?}").starts_with(expected), "{actual:?}")
+                }
+                (expected, actual) => panic!("expected {expected:?}, got {actual:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn identifies_itself() {
+        let (host, server) = stand_in::serve_once(200, "");
+        let client = AocClient::new(&host, "token").with_contact("me@example.com");
+
+        client.get(&client.url("/2023")).unwrap();
+
+        assert_eq!(
+            Some(concat!(
+                "fetch_day/",
+                env!("CARGO_PKG_VERSION"),
+                " (+https://github.com/maneac/aoc2023; me@example.com)"
+            )),
+            server.join().unwrap().header("user-agent")
+        );
+    }
+
+    #[test]
+    fn spaces_requests() {
+        let interval = Duration::from_millis(300);
+        let client = AocClient::new("", "token").with_min_interval(interval);
+
+        let start = Instant::now();
+        for _ in 0..2 {
+            let (host, server) = stand_in::serve_once(200, "");
+            client.get(&format!("{host}/2023")).unwrap();
+            server.join().unwrap();
+        }
+
+        assert!(start.elapsed() >= interval, "{:?}", start.elapsed());
+    }
+}
@@ -0,0 +1,489 @@
+use std::{
+    cell::OnceCell,
+    fmt::Display,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use clap::Args;
+use toml_edit::{value, Document};
+
+use crate::{
+    client::{AocClient, FetchError},
+    html,
+};
+
+#[derive(Args, Debug)]
+pub struct LoginOpts {
+    #[arg(
+        long,
+        value_name = "YYYY-MM-DD",
+        help = "When the session cookie expires, as shown by the browser"
+    )]
+    pub expires: Option<NaiveDate>,
+}
+
+/// Secrets read from the per-user credentials file, e.g.
+///
+/// ```toml
+/// session_token = "53616c7465645f5f..."
+/// session_expires = "2024-11-30"
+/// aes_key = "..."
+/// ```
+///
+/// Each key stands in for the environment variable of the same name with an
+/// `AOC_` prefix, which takes precedence when set. The file is only read once
+/// a value has to come from it.
+#[derive(Debug, Default)]
+pub struct Credentials {
+    path: Option<PathBuf>,
+    doc: OnceCell<Document>,
+}
+
+#[derive(Debug)]
+pub enum CredentialError {
+    Io {
+        path: PathBuf,
+        err: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        err: toml_edit::TomlError,
+    },
+    /// The file can be read by any user.
+    Exposed {
+        path: PathBuf,
+        mode: u32,
+    },
+    Missing {
+        var: String,
+        path: Option<PathBuf>,
+    },
+    /// Neither `XDG_CONFIG_HOME` nor `HOME` is set, so there's nowhere to save.
+    NoConfigDir,
+}
+
+impl Display for CredentialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CredentialError::Io { path, err } => write!(f, "{}: {err}", path.display()),
+            CredentialError::Parse { path, err } => write!(f, "{}: {err}", path.display()),
+            CredentialError::Exposed { path, mode } => write!(
+                f,
+                "{} is readable by any user (mode {mode:o}); run 'chmod 600 {}'",
+                path.display(),
+                path.display()
+            ),
+            CredentialError::Missing { var, path: None } => write!(f, "No '{var}' set"),
+            CredentialError::Missing {
+                var,
+                path: Some(path),
+            } => write!(
+                f,
+                "No '{var}' set, and no '{}' in {}",
+                file_key(var),
+                path.display()
+            ),
+            CredentialError::NoConfigDir => {
+                f.write_str("neither 'XDG_CONFIG_HOME' nor 'HOME' is set")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CredentialError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CredentialError::Io { err, .. } => Some(err),
+            CredentialError::Parse { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/fetch_day`, falling back to `~/.config`, or `None` if
+/// neither is set.
+pub fn config_dir() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_dir.join("fetch_day"))
+}
+
+/// `credentials.toml` in [`config_dir`].
+pub fn default_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("credentials.toml"))
+}
+
+/// `AOC_SESSION_TOKEN` -> `session_token`
+fn file_key(var: &str) -> String {
+    var.strip_prefix("AOC_").unwrap_or(var).to_lowercase()
+}
+
+impl Credentials {
+    /// Credentials backed by the file at `path`, if there is one, which isn't
+    /// read until it's needed.
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            doc: OnceCell::new(),
+        }
+    }
+
+    /// The file's contents, read on first use and refused if any user can
+    /// read it. A missing file is empty.
+    fn doc(&self) -> Result<&Document, CredentialError> {
+        if let Some(doc) = self.doc.get() {
+            return Ok(doc);
+        }
+        let doc = match &self.path {
+            Some(path) if path.exists() => read(path)?,
+            _ => Document::new(),
+        };
+        Ok(self.doc.get_or_init(|| doc))
+    }
+
+    /// The environment variable `var` if set, otherwise its key in the file.
+    pub fn get(&self, var: &str) -> Result<String, CredentialError> {
+        if let Ok(env) = std::env::var(var) {
+            return Ok(env);
+        }
+        self.stored(var)?.ok_or_else(|| CredentialError::Missing {
+            var: var.to_string(),
+            path: self.path.clone(),
+        })
+    }
+
+    /// The value for `var` in the file, ignoring the environment.
+    fn stored(&self, var: &str) -> Result<Option<String>, CredentialError> {
+        Ok(self
+            .doc()?
+            .get(&file_key(var))
+            .and_then(|item| item.as_str())
+            .map(str::to_string))
+    }
+
+    /// When the session cookie expires, if it was recorded along with `token`.
+    pub fn session_expires(&self, token: &str) -> Result<Option<NaiveDate>, CredentialError> {
+        if self.stored("AOC_SESSION_TOKEN")?.as_deref() != Some(token) {
+            return Ok(None);
+        }
+        Ok(self
+            .doc()?
+            .get("session_expires")
+            .and_then(|item| item.as_str())
+            .and_then(|date| date.parse().ok()))
+    }
+
+    /// Stores `token` in the file, keeping any other keys, and makes the file
+    /// readable by its owner only.
+    pub fn save_session(
+        &mut self,
+        token: &str,
+        expires: Option<NaiveDate>,
+    ) -> Result<PathBuf, CredentialError> {
+        let path = self.path.clone().ok_or(CredentialError::NoConfigDir)?;
+
+        let mut doc = self.doc()?.clone();
+        doc["session_token"] = value(token);
+        match expires {
+            Some(expires) => doc["session_expires"] = value(expires.to_string()),
+            None => {
+                doc.remove("session_expires");
+            }
+        }
+
+        let io_err = |err| CredentialError::Io {
+            path: path.clone(),
+            err,
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(io_err)?;
+        }
+        let mut file = private_file(&path).map_err(io_err)?;
+        file.write_all(doc.to_string().as_bytes()).map_err(io_err)?;
+        self.doc = OnceCell::from(doc);
+        Ok(path)
+    }
+}
+
+fn read(path: &Path) -> Result<Document, CredentialError> {
+    check_permissions(path)?;
+    let contents = fs::read_to_string(path).map_err(|err| CredentialError::Io {
+        path: path.to_path_buf(),
+        err,
+    })?;
+    contents
+        .parse::<Document>()
+        .map_err(|err| CredentialError::Parse {
+            path: path.to_path_buf(),
+            err,
+        })
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<(), CredentialError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)
+        .map_err(|err| CredentialError::Io {
+            path: path.to_path_buf(),
+            err,
+        })?
+        .permissions()
+        .mode()
+        & 0o777;
+    // Sharing with the file's group is left to its owner
+    if mode & 0o004 != 0 {
+        return Err(CredentialError::Exposed {
+            path: path.to_path_buf(),
+            mode,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<(), CredentialError> {
+    Ok(())
+}
+
+/// Opens `path` for writing, truncated and with mode `600`.
+#[cfg(unix)]
+fn private_file(path: &Path) -> std::io::Result<fs::File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // `mode` only applies when the file is created
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+fn private_file(path: &Path) -> std::io::Result<fs::File> {
+    fs::File::create(path)
+}
+
+/// Name of the account the session token belongs to, from the header of an
+/// authenticated page.
+pub fn account_name(client: &AocClient) -> Result<String, FetchError> {
+    let url = client.url("/settings");
+    let page = client.get(&url)?;
+    logged_in_user(&page).ok_or(FetchError::LoggedOut { url })
+}
+
+fn logged_in_user(page: &str) -> Option<String> {
+    let tree = html::parse(page);
+    html::find_all(&tree, "div")
+        .into_iter()
+        .find(|div| div.attr("class") == Some("user"))
+        .and_then(|user| {
+            user.children
+                .iter()
+                .find(|node| !node.is_whitespace())
+                .map(|node| node.text().trim().to_string())
+        })
+}
+
+pub fn describe_expiry(expires: Option<NaiveDate>, today: NaiveDate) -> String {
+    let Some(expires) = expires else {
+        return "The session cookie's expiry is unknown; record it with 'fetch_day login --expires YYYY-MM-DD'"
+            .to_string();
+    };
+    let days = (expires - today).num_days();
+    match days {
+        ..=-1 => format!("The session cookie expired on {expires}"),
+        0 => format!("The session cookie expires today ({expires})"),
+        1 => format!("The session cookie expires on {expires}, tomorrow"),
+        _ => format!("The session cookie expires on {expires}, in {days} days"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::stand_in, test_util::TempDir};
+
+    /// Credentials file path within `dir`.
+    fn credentials_path(dir: &TempDir) -> PathBuf {
+        dir.join("fetch_day").join("credentials.toml")
+    }
+
+    #[test]
+    fn save_then_load() {
+        let dir = TempDir::new("credentials_save");
+        let path = credentials_path(&dir);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            "# mine\naes_key = \"key\"\nsession_token = \"old\"\n",
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        let mut credentials = Credentials::new(Some(path.clone()));
+        credentials
+            .save_session("new", NaiveDate::from_ymd_opt(2024, 11, 30))
+            .unwrap();
+
+        assert_eq!(
+            "# mine\naes_key = \"key\"\nsession_token = \"new\"\nsession_expires = \"2024-11-30\"\n",
+            fs::read_to_string(&path).unwrap()
+        );
+        let credentials = Credentials::new(Some(path.clone()));
+        assert_eq!(
+            Some("new".to_string()),
+            credentials.stored("AOC_SESSION_TOKEN").unwrap()
+        );
+        assert_eq!(
+            Some("key".to_string()),
+            credentials.stored("AOC_AES_KEY").unwrap()
+        );
+        assert_eq!(None, credentials.stored("AOC_NEW_AES_KEY").unwrap());
+        assert_eq!(
+            NaiveDate::from_ymd_opt(2024, 11, 30),
+            credentials.session_expires("new").unwrap()
+        );
+        assert_eq!(None, credentials.session_expires("old").unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_world_readable_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new("credentials_readable");
+        let path = credentials_path(&dir);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "session_token = \"token\"\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        // Nothing is read until a value has to come from the file
+        let credentials = Credentials::new(Some(path.clone()));
+        let err = credentials.get("AOC_FETCH_DAY_UNSET").unwrap_err();
+        assert!(
+            matches!(err, CredentialError::Exposed { mode: 0o644, .. }),
+            "{err:?}"
+        );
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        let credentials = Credentials::new(Some(path));
+        assert_eq!(
+            Some("token".to_string()),
+            credentials.stored("AOC_SESSION_TOKEN").unwrap()
+        );
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        let dir = TempDir::new("credentials_missing");
+        let path = credentials_path(&dir);
+
+        let credentials = Credentials::new(Some(path.clone()));
+
+        assert_eq!(None, credentials.stored("AOC_SESSION_TOKEN").unwrap());
+        assert_eq!(
+            format!(
+                "No 'AOC_FETCH_DAY_UNSET' set, and no 'fetch_day_unset' in {}",
+                path.display()
+            ),
+            credentials
+                .get("AOC_FETCH_DAY_UNSET")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    mod account_name {
+        use super::*;
+
+        struct Case {
+            body: &'static str,
+            expected: Result<&'static str, &'static str>,
+        }
+
+        #[test]
+        fn logged_in() {
+            run(&Case {
+                body: include_str!("../fixtures/account/logged_in.html"),
+                expected: Ok("maneac"),
+            })
+        }
+
+        #[test]
+        fn logged_out() {
+            run(&Case {
+                body: include_str!("../fixtures/account/logged_out.html"),
+                expected: Err("LoggedOut"),
+            })
+        }
+
+        fn run(test: &Case) {
+            let (host, server) = stand_in::serve_once(200, test.body);
+            let client = AocClient::new(host, "token");
+
+            let actual = account_name(&client);
+            let request = server.join().unwrap();
+
+            assert_eq!("/settings", request.path);
+            assert_eq!(Some("session=token"), request.header("cookie"));
+            match (test.expected, actual) {
+                (Ok(expected), Ok(actual)) => assert_eq!(expected, actual),
+                (Err(expected), Err(actual)) => {
+                    assert!(format!("{actual:?}").starts_with(expected), "{actual:?}")
+                }
+                (expected, actual) => panic!("expected {expected:?}, got {actual:?}"),
+            }
+        }
+    }
+
+    mod describe_expiry {
+        use super::*;
+
+        struct Case {
+            expires: Option<NaiveDate>,
+            expected: &'static str,
+        }
+
+        #[test]
+        fn unknown() {
+            run(&Case {
+                expires: None,
+                expected: "The session cookie's expiry is unknown; record it with 'fetch_day login --expires YYYY-MM-DD'",
+            })
+        }
+
+        #[test]
+        fn future() {
+            run(&Case {
+                expires: NaiveDate::from_ymd_opt(2023, 12, 25),
+                expected: "The session cookie expires on 2023-12-25, in 20 days",
+            })
+        }
+
+        #[test]
+        fn expired() {
+            run(&Case {
+                expires: NaiveDate::from_ymd_opt(2023, 12, 1),
+                expected: "The session cookie expired on 2023-12-01",
+            })
+        }
+
+        fn run(test: &Case) {
+            let today = NaiveDate::from_ymd_opt(2023, 12, 5).unwrap();
+            assert_eq!(test.expected, describe_expiry(test.expires, today))
+        }
+    }
+}
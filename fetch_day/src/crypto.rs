@@ -0,0 +1,254 @@
+use std::fmt::Display;
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, OsRng},
+    Aes256Gcm, Key, KeyInit, Nonce,
+};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// Header of files whose key is derived from `AOC_AES_KEY` with Argon2id.
+///
+/// The full format is `v1$<base64 salt>$<base64 nonce + ciphertext>`. Files
+/// without a header are the legacy format, `<base64 nonce + ciphertext>`
+/// encrypted with the raw bytes of a 32 byte `AOC_AES_KEY`.
+const V1_HEADER: &str = "v1";
+
+#[derive(Debug)]
+pub enum CryptoError {
+    Decode(base64::DecodeError),
+    Malformed,
+    UnsupportedVersion(String),
+    /// Legacy files need the raw key, which must be exactly 32 bytes.
+    LegacyKeyLength(usize),
+    Kdf(argon2::Error),
+    Encrypt,
+    /// Freshly encrypted data didn't decrypt back to the plaintext.
+    RoundTrip,
+    /// Wrong key, or the file has been tampered with.
+    Authentication,
+}
+
+impl Display for CryptoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CryptoError::Decode(err) => write!(f, "invalid base64: {err}"),
+            CryptoError::Malformed => f.write_str("malformed encrypted data"),
+            CryptoError::UnsupportedVersion(version) => {
+                write!(f, "unsupported encrypted data version '{version}'")
+            }
+            CryptoError::LegacyKeyLength(len) => write!(
+                f,
+                "legacy encrypted data needs a 32 byte 'AOC_AES_KEY', got {len} bytes"
+            ),
+            CryptoError::Kdf(err) => write!(f, "key derivation failed: {err}"),
+            CryptoError::Encrypt => f.write_str("encryption failed"),
+            CryptoError::RoundTrip => f.write_str("encrypted data failed to round trip"),
+            CryptoError::Authentication => {
+                f.write_str("decryption failed: wrong key or corrupt data")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+impl From<base64::DecodeError> for CryptoError {
+    fn from(err: base64::DecodeError) -> Self {
+        CryptoError::Decode(err)
+    }
+}
+
+impl From<argon2::Error> for CryptoError {
+    fn from(err: argon2::Error) -> Self {
+        CryptoError::Kdf(err)
+    }
+}
+
+/// Passphrase used to encrypt and decrypt the input data.
+pub struct DataKey {
+    secret: Vec<u8>,
+}
+
+impl DataKey {
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: secret.into(),
+        }
+    }
+
+    /// Encrypts `plaintext` in the current format, with a fresh salt and nonce.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<String, CryptoError> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = self.derive(&salt)?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let nonce = Nonce::from(nonce);
+
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| CryptoError::Encrypt)?;
+
+        Ok(format!(
+            "{V1_HEADER}${}${}",
+            STANDARD.encode(salt),
+            STANDARD.encode(
+                nonce
+                    .iter()
+                    .chain(ciphertext.iter())
+                    .copied()
+                    .collect::<Vec<u8>>()
+            )
+        ))
+    }
+
+    /// Decrypts data in either the current or the legacy format.
+    pub fn decrypt(&self, encoded: &str) -> Result<Vec<u8>, CryptoError> {
+        let encoded = encoded.trim();
+
+        let Some((version, rest)) = encoded.split_once('$') else {
+            let key = self.legacy_cipher()?;
+            return open(&key, &STANDARD.decode(encoded)?);
+        };
+
+        if version != V1_HEADER {
+            return Err(CryptoError::UnsupportedVersion(version.to_string()));
+        }
+
+        let (salt, payload) = rest.split_once('$').ok_or(CryptoError::Malformed)?;
+        let cipher = self.derive(&STANDARD.decode(salt)?)?;
+        open(&cipher, &STANDARD.decode(payload)?)
+    }
+
+    fn derive(&self, salt: &[u8]) -> Result<Aes256Gcm, CryptoError> {
+        let mut key = [0u8; KEY_LEN];
+        Argon2::default().hash_password_into(&self.secret, salt, &mut key)?;
+        Ok(Aes256Gcm::new(&Key::<Aes256Gcm>::from(key)))
+    }
+
+    fn legacy_cipher(&self) -> Result<Aes256Gcm, CryptoError> {
+        if self.secret.len() != KEY_LEN {
+            return Err(CryptoError::LegacyKeyLength(self.secret.len()));
+        }
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.secret)))
+    }
+}
+
+fn open(cipher: &Aes256Gcm, payload: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if payload.len() < NONCE_LEN {
+        return Err(CryptoError::Malformed);
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| CryptoError::Authentication)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY_KEY: &str = "0123456789abcdef0123456789abcdef";
+
+    fn legacy_encrypt(plaintext: &[u8]) -> String {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(LEGACY_KEY.as_bytes()));
+        let nonce = Nonce::from([7u8; NONCE_LEN]);
+        let ciphertext = cipher.encrypt(&nonce, plaintext).unwrap();
+        STANDARD.encode(
+            nonce
+                .iter()
+                .chain(ciphertext.iter())
+                .copied()
+                .collect::<Vec<u8>>(),
+        )
+    }
+
+    #[test]
+    fn round_trip_with_passphrase() {
+        let key = DataKey::new("correct horse battery staple");
+
+        let encrypted = key.encrypt(b"1 2 3\n").unwrap();
+
+        assert!(encrypted.starts_with("v1$"));
+        assert_eq!(b"1 2 3\n".to_vec(), key.decrypt(&encrypted).unwrap());
+    }
+
+    #[test]
+    fn fresh_salt_and_nonce_per_file() {
+        let key = DataKey::new("passphrase");
+
+        assert_ne!(key.encrypt(b"data").unwrap(), key.encrypt(b"data").unwrap());
+    }
+
+    mod decrypt {
+        use super::*;
+
+        struct Case {
+            key: &'static str,
+            input: String,
+            expected: Result<&'static [u8], &'static str>,
+        }
+
+        #[test]
+        fn legacy() {
+            run(&Case {
+                key: LEGACY_KEY,
+                input: legacy_encrypt(b"legacy input"),
+                expected: Ok(b"legacy input"),
+            })
+        }
+
+        #[test]
+        fn legacy_with_passphrase() {
+            run(&Case {
+                key: "too short",
+                input: legacy_encrypt(b"legacy input"),
+                expected: Err("LegacyKeyLength"),
+            })
+        }
+
+        #[test]
+        fn wrong_key() {
+            run(&Case {
+                key: "wrong",
+                input: DataKey::new("right").encrypt(b"secret").unwrap(),
+                expected: Err("Authentication"),
+            })
+        }
+
+        #[test]
+        fn unsupported_version() {
+            run(&Case {
+                key: LEGACY_KEY,
+                input: "v9$AAAA$AAAA".to_string(),
+                expected: Err("UnsupportedVersion"),
+            })
+        }
+
+        #[test]
+        fn corrupt_base64() {
+            run(&Case {
+                key: LEGACY_KEY,
+                input: "not base64!".to_string(),
+                expected: Err("Decode"),
+            })
+        }
+
+        fn run(test: &Case) {
+            match (test.expected, DataKey::new(test.key).decrypt(&test.input)) {
+                (Ok(expected), Ok(actual)) => assert_eq!(expected, actual),
+                (Err(expected), Err(actual)) => {
+                    assert!(format!("{actual:?}").starts_with(expected), "{actual:?}")
+                }
+                (expected, actual) => panic!("expected {expected:?}, got {actual:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,516 @@
+use std::{
+    collections::BTreeSet,
+    fmt::Display,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    crypto::{CryptoError, DataKey},
+    days::DaySet,
+    error::{Error, WithPath},
+};
+
+pub const DATA_DIR: &str = "data";
+
+const ENCRYPTED_SUFFIX: &str = ".enc.txt";
+const PLAINTEXT_SUFFIX: &str = ".txt";
+
+/// Encrypted inputs in `dir`, sorted by name.
+pub fn encrypted_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    files_matching(dir, |name| name.ends_with(ENCRYPTED_SUFFIX))
+}
+
+/// Plaintext inputs in `dir`, sorted by name.
+pub fn plaintext_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    files_matching(dir, |name| {
+        name.ends_with(PLAINTEXT_SUFFIX) && !name.ends_with(ENCRYPTED_SUFFIX)
+    })
+}
+
+fn files_matching(dir: &Path, matches: impl Fn(&str) -> bool) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_path(dir)? {
+        let path = entry.with_path(dir)?.path();
+        if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(&matches)
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// `data/day_01.enc.txt` -> `data/day_01.txt`
+pub fn plaintext_path(encrypted: &Path) -> PathBuf {
+    let name = encrypted.file_name().unwrap_or_default().to_string_lossy();
+    encrypted.with_file_name(name.replace(ENCRYPTED_SUFFIX, PLAINTEXT_SUFFIX))
+}
+
+/// `data/day_01.txt` -> `data/day_01.enc.txt`
+pub fn encrypted_path(plaintext: &Path) -> PathBuf {
+    let name = plaintext.file_name().unwrap_or_default().to_string_lossy();
+    let stem = name.strip_suffix(PLAINTEXT_SUFFIX).unwrap_or(&name);
+    plaintext.with_file_name(format!("{stem}{ENCRYPTED_SUFFIX}"))
+}
+
+/// Day number of an input file, e.g. 5 for `data/day_05.enc.txt`.
+pub fn day_of(file: &Path) -> Option<u32> {
+    file.file_name()?
+        .to_str()?
+        .strip_prefix("day_")?
+        .split('.')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Where decrypted inputs are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecryptTarget {
+    /// Next to the encrypted input.
+    Alongside,
+    Dir(PathBuf),
+    /// A writer such as stdout; only valid for a single input.
+    Writer,
+}
+
+/// Decrypts the encrypted inputs in `dir` for `days`, or every day if `None`.
+///
+/// Plaintext files which already hold the decrypted contents are left untouched.
+pub fn decrypt_data(
+    dir: &Path,
+    key: &DataKey,
+    days: Option<&DaySet>,
+    target: &DecryptTarget,
+    writer: &mut impl Write,
+) -> Result<(), Error> {
+    let files = encrypted_files(dir)?
+        .into_iter()
+        .filter(|file| days.is_none_or(|days| day_of(file).is_some_and(|day| days.contains(day))))
+        .collect::<Vec<_>>();
+
+    if *target == DecryptTarget::Writer && files.len() != 1 {
+        return Err(Error::Usage(format!(
+            "writing to stdout needs exactly one input, found {}",
+            files.len()
+        )));
+    }
+
+    for file in files {
+        let contents = fs::read_to_string(&file).with_path(&file)?;
+
+        let decrypted = key.decrypt(&contents).map_err(|err| Error::Crypto {
+            path: file.clone(),
+            err,
+        })?;
+
+        let output = match target {
+            DecryptTarget::Writer => {
+                writer.write_all(&decrypted).with_path("stdout")?;
+                continue;
+            }
+            DecryptTarget::Alongside => plaintext_path(&file),
+            DecryptTarget::Dir(out_dir) => {
+                fs::create_dir_all(out_dir).with_path(out_dir)?;
+                out_dir.join(plaintext_path(&file).file_name().unwrap_or_default())
+            }
+        };
+
+        if fs::read(&output).is_ok_and(|existing| existing == decrypted) {
+            continue;
+        }
+        fs::write(&output, decrypted).with_path(&output)?;
+    }
+
+    Ok(())
+}
+
+/// (Re)creates the encrypted copy of every plaintext input in `dir`.
+pub fn encrypt_data(dir: &Path, key: &DataKey) -> Result<usize, Error> {
+    let files = plaintext_files(dir)?;
+    for file in &files {
+        let plaintext = fs::read(file).with_path(file)?;
+        let encrypted = encrypted_path(file);
+        let sealed = sealed(key, &plaintext).map_err(|err| Error::Crypto {
+            path: encrypted.clone(),
+            err,
+        })?;
+        replace(&encrypted, &sealed)?;
+    }
+
+    Ok(files.len())
+}
+
+/// Re-encrypts each of the encrypted `files` from `old` to `new`.
+///
+/// Every file is decrypted, re-encrypted and checked before any is replaced,
+/// so a wrong key or corrupt file leaves them all as they were.
+pub fn rotate_key(files: &[PathBuf], old: &DataKey, new: &DataKey) -> Result<usize, Error> {
+    let mut rotated = Vec::new();
+    for file in files {
+        let crypto_err = |err| Error::Crypto {
+            path: file.clone(),
+            err,
+        };
+        let plaintext = old
+            .decrypt(&fs::read_to_string(file).with_path(file)?)
+            .map_err(crypto_err)?;
+        let sealed = sealed(new, &plaintext).map_err(crypto_err)?;
+        rotated.push((file, sealed));
+    }
+
+    for (file, encrypted) in &rotated {
+        replace(file, encrypted)?;
+    }
+
+    Ok(rotated.len())
+}
+
+/// Encrypts `plaintext`, checking it decrypts back to the same bytes.
+pub fn sealed(key: &DataKey, plaintext: &[u8]) -> Result<String, CryptoError> {
+    let encrypted = key.encrypt(plaintext)?;
+    if key.decrypt(&encrypted)? != plaintext {
+        return Err(CryptoError::RoundTrip);
+    }
+    Ok(encrypted)
+}
+
+/// Writes via a temporary file so an interrupted write never truncates `path`.
+fn replace(path: &Path, contents: &str) -> Result<(), Error> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents).with_path(&tmp)?;
+    fs::rename(&tmp, path).with_path(path)?;
+    Ok(())
+}
+
+/// Result of checking one day's inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditStatus {
+    Ok,
+    /// The encrypted input failed to decrypt or authenticate.
+    DecryptFailed(String),
+    /// The plaintext input differs from the decrypted one.
+    Mismatch,
+    /// Only the plaintext input exists.
+    NotEncrypted,
+    /// Only the encrypted input exists.
+    NotDecrypted,
+    /// A solution crate exists, but neither input does.
+    NoInput,
+}
+
+impl AuditStatus {
+    pub fn is_problem(&self) -> bool {
+        *self != AuditStatus::Ok
+    }
+}
+
+impl Display for AuditStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditStatus::Ok => f.write_str("ok"),
+            AuditStatus::DecryptFailed(err) => write!(f, "decrypt failed: {err}"),
+            AuditStatus::Mismatch => f.write_str("plaintext differs from encrypted"),
+            AuditStatus::NotEncrypted => f.write_str("no encrypted input"),
+            AuditStatus::NotDecrypted => f.write_str("no plaintext input"),
+            AuditStatus::NoInput => f.write_str("crate has no input"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRow {
+    pub name: String,
+    pub encrypted: bool,
+    pub plaintext: bool,
+    pub has_crate: bool,
+    pub status: AuditStatus,
+}
+
+/// Checks the inputs in `data_dir` against each other and the crates in `crates_dir`.
+///
+/// Encrypted inputs are only decrypted in memory; nothing is written.
+pub fn audit(data_dir: &Path, crates_dir: &Path, key: &DataKey) -> Result<Vec<AuditRow>, Error> {
+    let mut names = BTreeSet::new();
+    if data_dir.is_dir() {
+        for file in encrypted_files(data_dir)?
+            .iter()
+            .chain(plaintext_files(data_dir)?.iter())
+        {
+            let name = file.file_name().unwrap_or_default().to_string_lossy();
+            let name = name.split('.').next().unwrap_or_default();
+            names.insert(name.to_string());
+        }
+    }
+    if crates_dir.is_dir() {
+        for entry in fs::read_dir(crates_dir).with_path(crates_dir)? {
+            let entry = entry.with_path(crates_dir)?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() && name.starts_with("day_") {
+                names.insert(name);
+            }
+        }
+    }
+
+    let mut rows = Vec::new();
+    for name in names {
+        let plaintext_file = data_dir.join(format!("{name}{PLAINTEXT_SUFFIX}"));
+        let encrypted_file = encrypted_path(&plaintext_file);
+        let (plaintext, encrypted) = (plaintext_file.is_file(), encrypted_file.is_file());
+
+        let status = match (encrypted, plaintext) {
+            (true, _) => match key
+                .decrypt(&fs::read_to_string(&encrypted_file).with_path(&encrypted_file)?)
+            {
+                Err(err) => AuditStatus::DecryptFailed(err.to_string()),
+                Ok(_) if !plaintext => AuditStatus::NotDecrypted,
+                Ok(decrypted)
+                    if decrypted != fs::read(&plaintext_file).with_path(&plaintext_file)? =>
+                {
+                    AuditStatus::Mismatch
+                }
+                Ok(_) => AuditStatus::Ok,
+            },
+            (false, true) => AuditStatus::NotEncrypted,
+            (false, false) => AuditStatus::NoInput,
+        };
+
+        rows.push(AuditRow {
+            has_crate: crates_dir.join(&name).is_dir(),
+            name,
+            encrypted,
+            plaintext,
+            status,
+        });
+    }
+
+    Ok(rows)
+}
+
+pub fn format_audit(rows: &[AuditRow]) -> String {
+    let mark = |present: bool| if present { "yes" } else { "-" };
+
+    let mut output = format!(
+        "{:<8} {:<9} {:<9} {:<5} {}\n",
+        "Day", "Encrypted", "Plaintext", "Crate", "Status"
+    );
+    for row in rows {
+        output.push_str(&format!(
+            "{:<8} {:<9} {:<9} {:<5} {}\n",
+            row.name,
+            mark(row.encrypted),
+            mark(row.plaintext),
+            mark(row.has_crate),
+            row.status
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn encrypt_then_decrypt() {
+        let dir = TempDir::new("encrypt_then_decrypt");
+        let key = DataKey::new("passphrase");
+        fs::write(dir.join("day_01.txt"), "1\n2\n").unwrap();
+
+        assert_eq!(1, encrypt_data(&dir, &key).unwrap());
+        fs::remove_file(dir.join("day_01.txt")).unwrap();
+        decrypt_data(&dir, &key, None, &DecryptTarget::Alongside, &mut Vec::new()).unwrap();
+
+        assert_eq!(
+            "1\n2\n",
+            fs::read_to_string(dir.join("day_01.txt")).unwrap()
+        );
+    }
+
+    #[test]
+    fn decrypt_selected_days_elsewhere() {
+        let dir = TempDir::new("decrypt_selected_days_elsewhere");
+        let out_dir = dir.join("out");
+        let key = DataKey::new("passphrase");
+        for day in 1..=3 {
+            fs::write(
+                dir.join(format!("day_0{day}.enc.txt")),
+                key.encrypt(day.to_string().as_bytes()).unwrap(),
+            )
+            .unwrap();
+        }
+
+        decrypt_data(
+            &dir,
+            &key,
+            Some(&"2..=3".parse().unwrap()),
+            &DecryptTarget::Dir(out_dir.clone()),
+            &mut Vec::new(),
+        )
+        .unwrap();
+
+        let mut written = fs::read_dir(&out_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        written.sort();
+        assert_eq!(vec!["day_02.txt", "day_03.txt"], written);
+        assert!(!dir.join("day_01.txt").exists());
+    }
+
+    #[test]
+    fn decrypt_to_writer() {
+        let dir = TempDir::new("decrypt_to_writer");
+        let key = DataKey::new("passphrase");
+        fs::write(dir.join("day_01.enc.txt"), key.encrypt(b"one").unwrap()).unwrap();
+        fs::write(dir.join("day_02.enc.txt"), key.encrypt(b"two").unwrap()).unwrap();
+        let mut out = Vec::new();
+
+        assert!(decrypt_data(&dir, &key, None, &DecryptTarget::Writer, &mut out).is_err());
+        decrypt_data(
+            &dir,
+            &key,
+            Some(&"2".parse().unwrap()),
+            &DecryptTarget::Writer,
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(b"two".to_vec(), out);
+        assert!(!dir.join("day_02.txt").exists());
+    }
+
+    #[test]
+    fn rotate_key_re_encrypts() {
+        let dir = TempDir::new("rotate_key_re_encrypts");
+        let (old, new) = (DataKey::new("old"), DataKey::new("new"));
+        fs::write(dir.join("day_01.enc.txt"), old.encrypt(b"one").unwrap()).unwrap();
+        fs::write(dir.join("day_02.enc.txt"), old.encrypt(b"two").unwrap()).unwrap();
+
+        assert_eq!(
+            2,
+            rotate_key(&encrypted_files(&dir).unwrap(), &old, &new).unwrap()
+        );
+
+        let contents = fs::read_to_string(dir.join("day_02.enc.txt")).unwrap();
+        assert_eq!(b"two".to_vec(), new.decrypt(&contents).unwrap());
+        assert!(old.decrypt(&contents).is_err());
+    }
+
+    #[test]
+    fn rotate_key_leaves_files_on_failure() {
+        let dir = TempDir::new("rotate_key_leaves_files_on_failure");
+        let (old, new) = (DataKey::new("old"), DataKey::new("new"));
+        let first = old.encrypt(b"one").unwrap();
+        fs::write(dir.join("day_01.enc.txt"), &first).unwrap();
+        fs::write(dir.join("day_02.enc.txt"), new.encrypt(b"two").unwrap()).unwrap();
+
+        assert!(rotate_key(&encrypted_files(&dir).unwrap(), &old, &new).is_err());
+
+        assert_eq!(
+            first,
+            fs::read_to_string(dir.join("day_01.enc.txt")).unwrap()
+        );
+    }
+
+    #[test]
+    fn audit_reports_problems() {
+        let dir = TempDir::new("audit_reports_problems");
+        let (data_dir, crates_dir) = (dir.join("data"), dir.join("rs"));
+        fs::create_dir_all(&data_dir).unwrap();
+        for day in ["day_01", "day_05", "day_06"] {
+            fs::create_dir_all(crates_dir.join(day)).unwrap();
+        }
+        let key = DataKey::new("key");
+
+        fs::write(data_dir.join("day_01.txt"), "one").unwrap();
+        fs::write(
+            data_dir.join("day_01.enc.txt"),
+            key.encrypt(b"one").unwrap(),
+        )
+        .unwrap();
+        fs::write(data_dir.join("day_02.txt"), "changed").unwrap();
+        fs::write(
+            data_dir.join("day_02.enc.txt"),
+            key.encrypt(b"two").unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            data_dir.join("day_03.enc.txt"),
+            DataKey::new("other").encrypt(b"three").unwrap(),
+        )
+        .unwrap();
+        fs::write(data_dir.join("day_04.txt"), "four").unwrap();
+        fs::write(
+            data_dir.join("day_05.enc.txt"),
+            key.encrypt(b"five").unwrap(),
+        )
+        .unwrap();
+
+        let statuses = audit(&data_dir, &crates_dir, &key)
+            .unwrap()
+            .into_iter()
+            .map(|row| (row.name, row.status))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                ("day_01".to_string(), AuditStatus::Ok),
+                ("day_02".to_string(), AuditStatus::Mismatch),
+                (
+                    "day_03".to_string(),
+                    AuditStatus::DecryptFailed(
+                        "decryption failed: wrong key or corrupt data".to_string()
+                    )
+                ),
+                ("day_04".to_string(), AuditStatus::NotEncrypted),
+                ("day_05".to_string(), AuditStatus::NotDecrypted),
+                ("day_06".to_string(), AuditStatus::NoInput),
+            ],
+            statuses
+        );
+    }
+
+    #[test]
+    fn format_audit_table() {
+        assert_eq!(
+            "Day      Encrypted Plaintext Crate Status\n\
+             day_01   yes       yes       yes   ok\n\
+             day_06   -         -         yes   crate has no input\n",
+            format_audit(&[
+                AuditRow {
+                    name: "day_01".to_string(),
+                    encrypted: true,
+                    plaintext: true,
+                    has_crate: true,
+                    status: AuditStatus::Ok,
+                },
+                AuditRow {
+                    name: "day_06".to_string(),
+                    encrypted: false,
+                    plaintext: false,
+                    has_crate: true,
+                    status: AuditStatus::NoInput,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(
+            Path::new("data/day_01.txt"),
+            plaintext_path(Path::new("data/day_01.enc.txt"))
+        );
+        assert_eq!(
+            Path::new("data/day_01.enc.txt"),
+            encrypted_path(Path::new("data/day_01.txt"))
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use std::{fmt::Display, str::FromStr};
+
+/// A set of puzzle days, parsed from e.g. `5`, `1..=11`, `1..12` or `3,5,9`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySet(Vec<u32>);
+
+impl DaySet {
+    pub fn contains(&self, day: u32) -> bool {
+        self.0.binary_search(&day).is_ok()
+    }
+
+    /// The days in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// The day, if the set holds exactly one.
+    pub fn single(&self) -> Option<u32> {
+        match self.0[..] {
+            [day] => Some(day),
+            _ => None,
+        }
+    }
+}
+
+impl From<u32> for DaySet {
+    fn from(day: u32) -> Self {
+        DaySet(vec![day])
+    }
+}
+
+/// The shortest form that parses back to the same set, e.g. `1..=3,7`.
+impl Display for DaySet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut runs: Vec<(u32, u32)> = Vec::new();
+        for &day in &self.0 {
+            match runs.last_mut() {
+                Some((_, end)) if *end + 1 == day => *end = day,
+                _ => runs.push((day, day)),
+            }
+        }
+
+        for (i, (start, end)) in runs.into_iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            match end - start {
+                0 => write!(f, "{start}")?,
+                1 => write!(f, "{start},{end}")?,
+                _ => write!(f, "{start}..={end}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DaySet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_day = |day: &str| -> Result<u32, String> {
+            let day = day
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("'{day}' is not a day"))?;
+            if !(1..=25).contains(&day) {
+                return Err(format!("day {day} is not in 1..=25"));
+            }
+            Ok(day)
+        };
+
+        let mut days = Vec::new();
+        for part in s.split(',') {
+            if let Some((start, end)) = part.split_once("..=") {
+                days.extend(parse_day(start)?..=parse_day(end)?);
+            } else if let Some((start, end)) = part.split_once("..") {
+                days.extend(parse_day(start)?..parse_day(end)?);
+            } else {
+                days.push(parse_day(part)?);
+            }
+        }
+        days.sort_unstable();
+        days.dedup();
+
+        if days.is_empty() {
+            return Err(format!("'{s}' contains no days"));
+        }
+        Ok(DaySet(days))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_str {
+        use super::*;
+
+        struct Case {
+            input: &'static str,
+            expected: Result<Vec<u32>, &'static str>,
+        }
+
+        #[test]
+        fn single() {
+            run(&Case {
+                input: "5",
+                expected: Ok(vec![5]),
+            })
+        }
+
+        #[test]
+        fn inclusive_range() {
+            run(&Case {
+                input: "1..=4",
+                expected: Ok(vec![1, 2, 3, 4]),
+            })
+        }
+
+        #[test]
+        fn exclusive_range() {
+            run(&Case {
+                input: "1..4",
+                expected: Ok(vec![1, 2, 3]),
+            })
+        }
+
+        #[test]
+        fn list() {
+            run(&Case {
+                input: "9,3,5,3",
+                expected: Ok(vec![3, 5, 9]),
+            })
+        }
+
+        #[test]
+        fn mixed() {
+            run(&Case {
+                input: "1..=2,7",
+                expected: Ok(vec![1, 2, 7]),
+            })
+        }
+
+        #[test]
+        fn out_of_range() {
+            run(&Case {
+                input: "20..=26",
+                expected: Err("day 26 is not in 1..=25"),
+            })
+        }
+
+        #[test]
+        fn empty_range() {
+            run(&Case {
+                input: "5..5",
+                expected: Err("'5..5' contains no days"),
+            })
+        }
+
+        #[test]
+        fn not_a_day() {
+            run(&Case {
+                input: "x",
+                expected: Err("'x' is not a day"),
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected.clone().map(DaySet).map_err(str::to_string),
+                test.input.parse::<DaySet>()
+            )
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for input in ["5", "1..=11", "3,5,9", "1,2,7..=9,25"] {
+            let days = input.parse::<DaySet>().unwrap();
+            assert_eq!(input, days.to_string());
+            assert_eq!(days, days.to_string().parse::<DaySet>().unwrap());
+        }
+    }
+}
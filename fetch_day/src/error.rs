@@ -0,0 +1,259 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use crate::{
+    calendar::NotUnlocked, client::FetchError, credentials::CredentialError, crypto::CryptoError,
+    leaderboard::LeaderboardError, template_dir::TemplateError,
+};
+
+/// Exit codes for each kind of failure, clear of the `0`-`7` used for
+/// submission verdicts. Scripts may rely on these, so they never change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    Usage = 10,
+    Network = 11,
+    /// The session token is missing, invalid or expired, or a credential is
+    /// missing or unsafe.
+    Auth = 12,
+    /// The puzzle hasn't unlocked yet.
+    Locked = 13,
+    Crypto = 14,
+    Template = 15,
+    Filesystem = 16,
+    /// A page, manifest or cache file wasn't in the expected format.
+    Parse = 17,
+}
+
+/// Shown under `--help`.
+pub const EXIT_CODES: &str = "\
+Exit codes:
+  0    success, including a correct answer
+  1    '--audit-data' found problems
+  2    submit: answer too high
+  3    submit: answer too low
+  4    submit: answer incorrect
+  5    submit: rate limited
+  6    submit: already solved
+  7    submit: unrecognised response
+  10   invalid arguments
+  11   network failure
+  12   missing, invalid or expired credentials
+  13   puzzle not unlocked yet
+  14   encryption or decryption failure
+  15   template failure
+  16   filesystem failure
+  17   unexpected page, manifest or cache contents";
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+/// Everything that can stop fetch_day.
+#[derive(Debug)]
+pub enum Error {
+    /// Options which can't be used together, or a command given the wrong ones.
+    Usage(String),
+    Fetch(FetchError),
+    Credentials(CredentialError),
+    NotUnlocked(NotUnlocked),
+    Crypto {
+        path: PathBuf,
+        err: CryptoError,
+    },
+    Template(TemplateError),
+    /// A built-in template failed to render.
+    Render {
+        template: &'static str,
+        err: sailfish::RenderError,
+    },
+    Io {
+        path: PathBuf,
+        err: std::io::Error,
+    },
+    /// A TOML manifest which couldn't be parsed or lacks what's needed.
+    Manifest {
+        path: PathBuf,
+        reason: String,
+    },
+    /// A page from AoC without the expected structure.
+    Html {
+        url: String,
+        reason: String,
+    },
+    Leaderboard(LeaderboardError),
+}
+
+impl Error {
+    pub fn exit_status(&self) -> ExitStatus {
+        match self {
+            Error::Usage(_) => ExitStatus::Usage,
+            Error::Fetch(err) | Error::Leaderboard(LeaderboardError::Fetch(err)) => match err {
+                FetchError::Request(_) | FetchError::Status { .. } => ExitStatus::Network,
+                FetchError::LoggedOut { .. } => ExitStatus::Auth,
+                FetchError::Locked { .. } => ExitStatus::Locked,
+            },
+            Error::Credentials(_) => ExitStatus::Auth,
+            Error::NotUnlocked(_) => ExitStatus::Locked,
+            Error::Crypto { .. } => ExitStatus::Crypto,
+            Error::Template(_) | Error::Render { .. } => ExitStatus::Template,
+            Error::Io { .. } | Error::Leaderboard(LeaderboardError::Io { .. }) => {
+                ExitStatus::Filesystem
+            }
+            Error::Manifest { .. }
+            | Error::Html { .. }
+            | Error::Leaderboard(LeaderboardError::Parse { .. }) => ExitStatus::Parse,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Usage(reason) => f.write_str(reason),
+            Error::Fetch(err) => err.fmt(f),
+            Error::Credentials(err) => err.fmt(f),
+            Error::NotUnlocked(err) => err.fmt(f),
+            Error::Crypto { path, err } => write!(f, "{}: {err}", path.display()),
+            Error::Template(err) => err.fmt(f),
+            Error::Render { template, err } => write!(f, "rendering {template}: {err}"),
+            Error::Io { path, err } => write!(f, "{}: {err}", path.display()),
+            Error::Manifest { path, reason } => write!(f, "{}: {reason}", path.display()),
+            Error::Html { url, reason } => write!(f, "{url}: {reason}"),
+            Error::Leaderboard(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Fetch(err) => err.source(),
+            Error::Crypto { err, .. } => Some(err),
+            Error::Render { err, .. } => Some(err),
+            Error::Io { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<FetchError> for Error {
+    fn from(err: FetchError) -> Self {
+        Error::Fetch(err)
+    }
+}
+
+impl From<CredentialError> for Error {
+    fn from(err: CredentialError) -> Self {
+        Error::Credentials(err)
+    }
+}
+
+impl From<NotUnlocked> for Error {
+    fn from(err: NotUnlocked) -> Self {
+        Error::NotUnlocked(err)
+    }
+}
+
+impl From<TemplateError> for Error {
+    fn from(err: TemplateError) -> Self {
+        Error::Template(err)
+    }
+}
+
+impl From<LeaderboardError> for Error {
+    fn from(err: LeaderboardError) -> Self {
+        Error::Leaderboard(err)
+    }
+}
+
+/// Attaches the path an I/O error happened at.
+pub trait WithPath<T> {
+    fn with_path(self, path: impl AsRef<Path>) -> Result<T, Error>;
+}
+
+impl<T> WithPath<T> for std::io::Result<T> {
+    fn with_path(self, path: impl AsRef<Path>) -> Result<T, Error> {
+        self.map_err(|err| Error::Io {
+            path: path.as_ref().to_path_buf(),
+            err,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod exit_status {
+        use super::*;
+
+        struct Case {
+            error: Error,
+            expected: u8,
+        }
+
+        #[test]
+        fn logged_out() {
+            run(Case {
+                error: Error::Fetch(FetchError::LoggedOut {
+                    url: "https://adventofcode.com/2023/day/5/input".to_string(),
+                }),
+                expected: 12,
+            })
+        }
+
+        #[test]
+        fn locked_page() {
+            run(Case {
+                error: Error::Fetch(FetchError::Locked {
+                    url: "https://adventofcode.com/2023/day/25".to_string(),
+                }),
+                expected: 13,
+            })
+        }
+
+        #[test]
+        fn leaderboard_cache() {
+            run(Case {
+                error: Error::Leaderboard(LeaderboardError::Io {
+                    path: PathBuf::from(".cache/leaderboard/2023_1.json"),
+                    err: std::io::Error::other("full"),
+                }),
+                expected: 16,
+            })
+        }
+
+        #[test]
+        fn decrypt() {
+            run(Case {
+                error: Error::Crypto {
+                    path: PathBuf::from("data/2023/day_05.enc.txt"),
+                    err: CryptoError::Authentication,
+                },
+                expected: 14,
+            })
+        }
+
+        fn run(test: Case) {
+            assert_eq!(test.expected, test.error.exit_status() as u8)
+        }
+    }
+
+    #[test]
+    fn io_errors_name_the_path() {
+        let path = Path::new("data/2023/missing.txt");
+
+        let err = std::fs::read_to_string(path).with_path(path).unwrap_err();
+
+        assert!(
+            err.to_string().starts_with("data/2023/missing.txt: "),
+            "{err}"
+        );
+        assert_eq!(ExitStatus::Filesystem, err.exit_status());
+    }
+}
@@ -0,0 +1,235 @@
+use crate::html::{self, Element, Node};
+
+/// The worked example from one part of a puzzle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Example {
+    /// The `<pre><code>` block following "For example".
+    pub input: Option<String>,
+    /// The last emphasised `<code><em>` value, which is the example's answer.
+    pub answer: Option<String>,
+}
+
+impl Example {
+    /// Finds the example in the `article` describing one part of a puzzle.
+    pub fn extract(article: &Element) -> Self {
+        let mut after_for_example = false;
+        let mut input = None;
+        for node in &article.children {
+            let Node::Element(el) = node else {
+                continue;
+            };
+            match el.tag.as_str() {
+                "p" if el.text().to_lowercase().contains("for example") => after_for_example = true,
+                "pre" if after_for_example && el.contains_tag("code") => {
+                    input = Some(el.text().trim_end_matches('\n').to_string());
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let answer = html::find_all(&article.children, "code")
+            .into_iter()
+            .rev()
+            .find_map(|code| {
+                let mut children = code.children.iter().filter(|child| !child.is_whitespace());
+                match (children.next(), children.next()) {
+                    (Some(Node::Element(em)), None) if em.tag == "em" => Some(em.text()),
+                    _ => None,
+                }
+            });
+
+        Self { input, answer }
+    }
+
+    /// The example input as a Rust string literal, keeping its line breaks.
+    pub fn input_literal(&self) -> String {
+        let input = self.input.as_deref().unwrap_or_default();
+        format!("\"{}\"", input.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    /// The expected answer as a `usize` expression for the generated tests,
+    /// or `todo!()` when there isn't a numeric one.
+    pub fn expected_literal(&self) -> String {
+        self.answer
+            .as_deref()
+            .filter(|answer| answer.parse::<usize>().is_ok())
+            .unwrap_or("todo!()")
+            .to_string()
+    }
+}
+
+/// Fills in the part 2 example expectation of a generated `lib.rs`.
+///
+/// Returns `None` if the expectation isn't a `todo!()` any more.
+pub fn patch_part_2(lib_rs: &str, expected: &str) -> Option<String> {
+    let part_2 = lib_rs.find("mod part_2")?;
+    let end = part_2 + lib_rs[part_2..].find("fn run(")?;
+    let todo = part_2 + lib_rs[part_2..end].find("expected: todo!()")?;
+
+    let mut patched = lib_rs.to_string();
+    patched.replace_range(
+        todo..todo + "expected: todo!()".len(),
+        &format!("expected: {expected}"),
+    );
+    Some(patched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod extract {
+        use super::*;
+
+        struct Case {
+            input: &'static str,
+            expected: Vec<Example>,
+        }
+
+        #[test]
+        fn puzzle() {
+            run(&Case {
+                input: include_str!("../fixtures/instructions/puzzle.html"),
+                expected: vec![
+                    Example {
+                        input: Some("red 3\nblue 12\nred 7".to_string()),
+                        answer: Some("22".to_string()),
+                    },
+                    Example::default(),
+                ],
+            })
+        }
+
+        #[test]
+        fn saved_2023_day_01() {
+            run(&Case {
+                input: include_str!("../fixtures/instructions/2023/day_01.html"),
+                expected: vec![
+                    Example {
+                        input: Some("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet".to_string()),
+                        answer: Some("142".to_string()),
+                    },
+                    Example {
+                        input: Some(
+                            "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n\
+                             4nineeightseven2\nzoneight234\n7pqrstsixteen"
+                                .to_string(),
+                        ),
+                        answer: Some("281".to_string()),
+                    },
+                ],
+            })
+        }
+
+        #[test]
+        fn saved_2023_day_07() {
+            let hands = "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483";
+            run(&Case {
+                input: include_str!("../fixtures/instructions/2023/day_07.html"),
+                expected: vec![
+                    Example {
+                        input: Some(hands.to_string()),
+                        answer: Some("6440".to_string()),
+                    },
+                    Example {
+                        input: Some(hands.to_string()),
+                        answer: Some("5905".to_string()),
+                    },
+                ],
+            })
+        }
+
+        #[test]
+        fn pre_before_example() {
+            run(&Case {
+                input: "<article><p>The map:</p><pre><code>#..#</code></pre>\
+                        <p>For example, suppose:</p><pre><code>1 \"2\"\n3\n</code></pre>\
+                        <p>That makes <em><code>4</code></em>, so <code><em>6</em></code>.</p>\
+                        </article>",
+                expected: vec![Example {
+                    input: Some("1 \"2\"\n3".to_string()),
+                    answer: Some("6".to_string()),
+                }],
+            })
+        }
+
+        fn run(test: &Case) {
+            let tree = html::parse(test.input);
+            let examples = html::find_all(&tree, "article")
+                .into_iter()
+                .map(Example::extract)
+                .collect::<Vec<_>>();
+            assert_eq!(test.expected, examples)
+        }
+    }
+
+    #[test]
+    fn literals() {
+        let example = Example {
+            input: Some("a \\ \"b\"\nc".to_string()),
+            answer: Some("red".to_string()),
+        };
+
+        assert_eq!("\"a \\\\ \\\"b\\\"\nc\"", example.input_literal());
+        assert_eq!("todo!()", example.expected_literal());
+    }
+
+    mod patch_part_2 {
+        use super::*;
+
+        const LIB_RS: &str = "mod part_1 {
+    fn example() {
+        run(&Case {
+            expected: 142,
+        })
+    }
+
+    fn run(test: &Case) {}
+}
+
+mod part_2 {
+    fn example() {
+        run(&Case {
+            expected: todo!(),
+        })
+    }
+
+    fn run(test: &Case) {}
+}
+";
+
+        struct Case {
+            input: &'static str,
+            expected: Option<&'static str>,
+        }
+
+        #[test]
+        fn todo() {
+            run(&Case {
+                input: LIB_RS,
+                expected: Some("expected: 281,"),
+            })
+        }
+
+        #[test]
+        fn already_filled() {
+            run(&Case {
+                input: "mod part_1 {\n    expected: todo!(),\n}\nmod part_2 {\n    expected: 281,\n    fn run() {}\n}\n",
+                expected: None,
+            })
+        }
+
+        fn run(test: &Case) {
+            let patched = patch_part_2(test.input, "281");
+            match (test.expected, patched) {
+                (Some(expected), Some(patched)) => {
+                    let part_2 = &patched[patched.find("mod part_2").unwrap()..];
+                    assert!(part_2.contains(expected), "{patched}");
+                    assert_eq!(test.input.replace("todo!()", "281"), patched);
+                }
+                (expected, patched) => assert_eq!(expected.map(str::to_string), patched),
+            }
+        }
+    }
+}
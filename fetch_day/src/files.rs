@@ -0,0 +1,344 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use similar::TextDiff;
+
+use crate::error::{Error, WithPath};
+
+/// What to do when a template would replace an existing file with different contents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OnConflict {
+    /// Keep the existing file
+    #[default]
+    Skip,
+    /// Move the existing file to `<name>.bak` (or `.bak.1`, ... if taken) and write the template
+    Backup,
+    /// Keep the existing file and print how the template differs from it
+    Diff,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Created,
+    Unchanged,
+    Overwritten,
+    Skipped,
+    /// Written after moving the existing file to the given backup.
+    BackedUp(PathBuf),
+    /// Kept the existing file; holds the unified diff to the template.
+    Diffed(String),
+    WouldCreate,
+    WouldModify,
+}
+
+/// Writes generated files, protecting existing ones from being clobbered.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileWriter {
+    pub on_conflict: OnConflict,
+    /// Overwrite conflicting files regardless of `on_conflict`.
+    pub force: bool,
+    /// Report what would be written without touching the filesystem.
+    pub dry_run: bool,
+}
+
+impl FileWriter {
+    /// Writes a freshly rendered template, applying the conflict policy to existing files.
+    pub fn write(&self, path: &Path, contents: &str) -> Result<Outcome, Error> {
+        let outcome = self.write_with(path, contents, self.force)?;
+        report(path, &outcome);
+        Ok(outcome)
+    }
+
+    /// Writes a file which is expected to be regenerated, such as a README,
+    /// only honouring `dry_run`.
+    pub fn update(&self, path: &Path, contents: &str) -> Result<Outcome, Error> {
+        let outcome = self.write_with(path, contents, true)?;
+        report(path, &outcome);
+        Ok(outcome)
+    }
+
+    fn write_with(&self, path: &Path, contents: &str, overwrite: bool) -> Result<Outcome, Error> {
+        let existing = match fs::read_to_string(path) {
+            Ok(existing) => Some(existing),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err).with_path(path),
+        };
+
+        let Some(existing) = existing else {
+            if self.dry_run {
+                return Ok(Outcome::WouldCreate);
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).with_path(parent)?;
+            }
+            fs::write(path, contents).with_path(path)?;
+            return Ok(Outcome::Created);
+        };
+
+        if existing == contents {
+            return Ok(Outcome::Unchanged);
+        }
+
+        let on_conflict = if overwrite {
+            None
+        } else {
+            Some(self.on_conflict)
+        };
+
+        match on_conflict {
+            Some(OnConflict::Skip) => Ok(Outcome::Skipped),
+            Some(OnConflict::Diff) => Ok(Outcome::Diffed(unified_diff(path, &existing, contents))),
+            _ if self.dry_run => Ok(Outcome::WouldModify),
+            Some(OnConflict::Backup) => {
+                let backup = backup_path(path);
+                fs::rename(path, &backup).with_path(&backup)?;
+                fs::write(path, contents).with_path(path)?;
+                Ok(Outcome::BackedUp(backup))
+            }
+            None => {
+                fs::write(path, contents).with_path(path)?;
+                Ok(Outcome::Overwritten)
+            }
+        }
+    }
+}
+
+fn report(path: &Path, outcome: &Outcome) {
+    let path = path.display();
+    match outcome {
+        Outcome::Created | Outcome::Unchanged | Outcome::Overwritten => {}
+        Outcome::Skipped => {
+            println!("Skipped existing {path}; use '--force' to overwrite it")
+        }
+        Outcome::BackedUp(backup) => {
+            println!("Moved existing {path} to {}", backup.display())
+        }
+        Outcome::Diffed(diff) => print!("{diff}"),
+        Outcome::WouldCreate => println!("Would create {path}"),
+        Outcome::WouldModify => println!("Would modify {path}"),
+    }
+}
+
+/// `src/lib.rs` -> `src/lib.rs.bak`, or the first of `src/lib.rs.bak.1`,
+/// `src/lib.rs.bak.2`, ... not already taken by an earlier backup.
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    let mut backup = path.with_file_name(&name);
+    for n in 1.. {
+        if !backup.exists() {
+            break;
+        }
+        let mut numbered = name.clone();
+        numbered.push(format!(".{n}"));
+        backup = path.with_file_name(numbered);
+    }
+    backup
+}
+
+fn unified_diff(path: &Path, existing: &str, template: &str) -> String {
+    let path = path.display().to_string();
+    TextDiff::from_lines(existing, template)
+        .unified_diff()
+        .header(&path, &format!("{path} (template)"))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    /// Directory under the system temp dir holding a single `file.rs`.
+    fn temp_file(name: &str, contents: Option<&str>) -> (TempDir, PathBuf) {
+        let dir = TempDir::new(name);
+        let file = dir.join("file.rs");
+        if let Some(contents) = contents {
+            fs::write(&file, contents).unwrap();
+        }
+        (dir, file)
+    }
+
+    mod write {
+        use super::*;
+
+        struct Case {
+            name: &'static str,
+            writer: FileWriter,
+            existing: Option<&'static str>,
+            expected: Outcome,
+            expected_contents: Option<&'static str>,
+        }
+
+        #[test]
+        fn creates_missing() {
+            run(&Case {
+                name: "creates_missing",
+                writer: FileWriter::default(),
+                existing: None,
+                expected: Outcome::Created,
+                expected_contents: Some("template"),
+            })
+        }
+
+        #[test]
+        fn unchanged() {
+            run(&Case {
+                name: "unchanged",
+                writer: FileWriter::default(),
+                existing: Some("template"),
+                expected: Outcome::Unchanged,
+                expected_contents: Some("template"),
+            })
+        }
+
+        #[test]
+        fn skips_existing() {
+            run(&Case {
+                name: "skips_existing",
+                writer: FileWriter::default(),
+                existing: Some("solution"),
+                expected: Outcome::Skipped,
+                expected_contents: Some("solution"),
+            })
+        }
+
+        #[test]
+        fn diffs_existing() {
+            run(&Case {
+                name: "diffs_existing",
+                writer: FileWriter {
+                    on_conflict: OnConflict::Diff,
+                    ..Default::default()
+                },
+                existing: Some("a\nsolution\n"),
+                expected: Outcome::Diffed(
+                    "--- DIR/file.rs\n\
+                     +++ DIR/file.rs (template)\n\
+                     @@ -1,2 +1,2 @@\n a\n-solution\n+template\n"
+                        .to_string(),
+                ),
+                expected_contents: Some("a\nsolution\n"),
+            })
+        }
+
+        #[test]
+        fn backs_up_existing() {
+            run(&Case {
+                name: "backs_up_existing",
+                writer: FileWriter {
+                    on_conflict: OnConflict::Backup,
+                    ..Default::default()
+                },
+                existing: Some("solution"),
+                expected: Outcome::BackedUp(PathBuf::from("DIR/file.rs.bak")),
+                expected_contents: Some("template"),
+            })
+        }
+
+        #[test]
+        fn forced() {
+            run(&Case {
+                name: "forced",
+                writer: FileWriter {
+                    force: true,
+                    ..Default::default()
+                },
+                existing: Some("solution"),
+                expected: Outcome::Overwritten,
+                expected_contents: Some("template"),
+            })
+        }
+
+        #[test]
+        fn dry_run_create() {
+            run(&Case {
+                name: "dry_run_create",
+                writer: FileWriter {
+                    dry_run: true,
+                    ..Default::default()
+                },
+                existing: None,
+                expected: Outcome::WouldCreate,
+                expected_contents: None,
+            })
+        }
+
+        #[test]
+        fn dry_run_forced() {
+            run(&Case {
+                name: "dry_run_forced",
+                writer: FileWriter {
+                    dry_run: true,
+                    force: true,
+                    ..Default::default()
+                },
+                existing: Some("solution"),
+                expected: Outcome::WouldModify,
+                expected_contents: Some("solution"),
+            })
+        }
+
+        fn run(test: &Case) {
+            let (dir, file) = temp_file(test.name, test.existing);
+            let template = if test.existing.is_some_and(|e| e.ends_with('\n')) {
+                "a\ntemplate\n"
+            } else {
+                "template"
+            };
+
+            let outcome = test.writer.write(&file, template).unwrap();
+
+            let dir_str = dir.display().to_string();
+            let outcome = match outcome {
+                Outcome::Diffed(diff) => Outcome::Diffed(diff.replace(&dir_str, "DIR")),
+                Outcome::BackedUp(backup) => {
+                    assert_eq!(test.existing, fs::read_to_string(&backup).ok().as_deref());
+                    Outcome::BackedUp(Path::new("DIR").join(backup.strip_prefix(&dir).unwrap()))
+                }
+                outcome => outcome,
+            };
+            assert_eq!(test.expected, outcome);
+            assert_eq!(
+                test.expected_contents,
+                fs::read_to_string(&file).ok().as_deref()
+            );
+        }
+    }
+
+    #[test]
+    fn backup_keeps_earlier_backups() {
+        let (dir, file) = temp_file("backup_keeps_earlier_backups", Some("first"));
+        let writer = FileWriter {
+            on_conflict: OnConflict::Backup,
+            ..Default::default()
+        };
+
+        for contents in ["second", "third"] {
+            writer.write(&file, contents).unwrap();
+        }
+
+        assert_eq!("third", fs::read_to_string(&file).unwrap());
+        assert_eq!(
+            "first",
+            fs::read_to_string(dir.join("file.rs.bak")).unwrap()
+        );
+        assert_eq!(
+            "second",
+            fs::read_to_string(dir.join("file.rs.bak.1")).unwrap()
+        );
+    }
+
+    #[test]
+    fn update_overwrites() {
+        let (_dir, file) = temp_file("update_overwrites", Some("part 1"));
+
+        let outcome = FileWriter::default().update(&file, "part 2").unwrap();
+
+        assert_eq!(Outcome::Overwritten, outcome);
+        assert_eq!("part 2", fs::read_to_string(&file).unwrap());
+    }
+}
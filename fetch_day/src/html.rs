@@ -0,0 +1,426 @@
+/// Elements which never have children or a closing tag.
+const VOID_TAGS: [&str; 8] = ["br", "hr", "img", "input", "link", "meta", "source", "wbr"];
+
+/// Elements whose contents are raw text up to their closing tag.
+const RAW_TEXT_TAGS: [&str; 2] = ["script", "style"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Node {
+    /// Concatenated text content of the node and all of its descendants.
+    pub fn text(&self) -> String {
+        match self {
+            Node::Element(el) => el.text(),
+            Node::Text(text) => text.to_owned(),
+        }
+    }
+
+    pub fn is_whitespace(&self) -> bool {
+        matches!(self, Node::Text(text) if text.trim().is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Element {
+    pub tag: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn text(&self) -> String {
+        self.children.iter().map(Node::text).collect()
+    }
+
+    /// Whether any descendant element has the given tag.
+    pub fn contains_tag(&self, tag: &str) -> bool {
+        !find_all(&self.children, tag).is_empty()
+    }
+}
+
+/// Returns every element with the given tag, in document order.
+pub fn find_all<'a>(nodes: &'a [Node], tag: &str) -> Vec<&'a Element> {
+    let mut found = Vec::new();
+    for node in nodes {
+        if let Node::Element(el) = node {
+            if el.tag == tag {
+                found.push(el);
+            }
+            found.extend(find_all(&el.children, tag));
+        }
+    }
+    found
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Start {
+        tag: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    End(String),
+    Text(String),
+}
+
+/// Parses an HTML document into a tree.
+///
+/// Malformed markup never fails: stray closing tags are dropped, unclosed
+/// elements are closed at the end of the input, and `<p>`/`<li>` are
+/// implicitly closed by a sibling of the same kind.
+pub fn parse(html: &str) -> Vec<Node> {
+    let mut stack = vec![Element::default()];
+
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => {
+                let children = &mut stack.last_mut().unwrap().children;
+                match children.last_mut() {
+                    Some(Node::Text(prev)) => prev.push_str(&text),
+                    _ => children.push(Node::Text(text)),
+                }
+            }
+            Token::Start {
+                tag,
+                attrs,
+                self_closing,
+            } => {
+                if tag == "p" || tag == "li" {
+                    let boundary = stack
+                        .iter()
+                        .rposition(|el| matches!(el.tag.as_str(), "ul" | "ol" | "pre"))
+                        .unwrap_or(0);
+                    if let Some(open) = stack.iter().rposition(|el| el.tag == tag) {
+                        if open > boundary {
+                            close_to(&mut stack, open);
+                        }
+                    }
+                }
+
+                let el = Element {
+                    tag,
+                    attrs,
+                    children: Vec::new(),
+                };
+                if self_closing || VOID_TAGS.contains(&el.tag.as_str()) {
+                    stack.last_mut().unwrap().children.push(Node::Element(el));
+                } else {
+                    stack.push(el);
+                }
+            }
+            Token::End(tag) => {
+                if let Some(open) = stack.iter().skip(1).rposition(|el| el.tag == tag) {
+                    close_to(&mut stack, open + 1);
+                }
+            }
+        }
+    }
+
+    close_to(&mut stack, 1);
+    stack.pop().unwrap().children
+}
+
+/// Pops elements off the stack until it has `len` entries, attaching each to its parent.
+fn close_to(stack: &mut Vec<Element>, len: usize) {
+    while stack.len() > len {
+        let el = stack.pop().unwrap();
+        stack.last_mut().unwrap().children.push(Node::Element(el));
+    }
+}
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some(end_tag) = rest.strip_prefix("</") {
+            let end = end_tag.find('>').unwrap_or(end_tag.len());
+            let tag = end_tag[..end].trim().to_ascii_lowercase();
+            if !tag.is_empty() {
+                tokens.push(Token::End(tag));
+            }
+            rest = end_tag.get(end + 1..).unwrap_or("");
+        } else if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            let (token, remaining) = start_tag(&rest[1..]);
+            rest = remaining;
+
+            if let Token::Start { tag, .. } = &token {
+                if RAW_TEXT_TAGS.contains(&tag.as_str()) {
+                    let close = format!("</{tag}");
+                    let end = rest.to_ascii_lowercase().find(&close).unwrap_or(rest.len());
+                    let raw = &rest[..end];
+                    rest = &rest[end..];
+                    tokens.push(token);
+                    if !raw.is_empty() {
+                        tokens.push(Token::Text(raw.to_string()));
+                    }
+                    continue;
+                }
+            }
+            tokens.push(token);
+        } else {
+            let first = rest.chars().next().map_or(1, char::len_utf8);
+            let end = rest[first..]
+                .find('<')
+                .map_or(rest.len(), |idx| idx + first);
+            tokens.push(Token::Text(unescape(&rest[..end])));
+            rest = &rest[end..];
+        }
+    }
+
+    tokens
+}
+
+/// Parses a start tag following its `<`, returning the token and the remaining input.
+fn start_tag(input: &str) -> (Token, &str) {
+    let name_end = input
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(input.len());
+    let tag = input[..name_end].to_ascii_lowercase();
+    let mut rest = &input[name_end..];
+
+    let mut attrs = Vec::new();
+    let mut self_closing = false;
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        if let Some(r) = rest.strip_prefix('>') {
+            rest = r;
+            break;
+        }
+        if let Some(r) = rest.strip_prefix("/>") {
+            self_closing = true;
+            rest = r;
+            break;
+        }
+        if let Some(r) = rest.strip_prefix('/') {
+            rest = r;
+            continue;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let value = if let Some(r) = rest.strip_prefix('=') {
+            let r = r.trim_start();
+            match r.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let r = &r[1..];
+                    let end = r.find(quote).unwrap_or(r.len());
+                    rest = r.get(end + 1..).unwrap_or("");
+                    unescape(&r[..end])
+                }
+                _ => {
+                    let end = r
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(r.len());
+                    rest = &r[end..];
+                    unescape(&r[..end])
+                }
+            }
+        } else {
+            String::new()
+        };
+
+        attrs.push((name, value));
+    }
+
+    (
+        Token::Start {
+            tag,
+            attrs,
+            self_closing,
+        },
+        rest,
+    )
+}
+
+/// Decodes every named and numeric character reference.
+fn unescape(text: &str) -> String {
+    htmlize::unescape(text).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn el(tag: &str, attrs: &[(&str, &str)], children: Vec<Node>) -> Node {
+        Node::Element(Element {
+            tag: tag.to_string(),
+            attrs: attrs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            children,
+        })
+    }
+
+    fn text(text: &str) -> Node {
+        Node::Text(text.to_string())
+    }
+
+    mod parse {
+        use super::*;
+
+        struct Case {
+            input: &'static str,
+            expected: Vec<Node>,
+        }
+
+        #[test]
+        fn nested() {
+            run(&Case {
+                input: r#"<p>Some <em>very <code>deep</code></em> text</p>"#,
+                expected: vec![el(
+                    "p",
+                    &[],
+                    vec![
+                        text("Some "),
+                        el(
+                            "em",
+                            &[],
+                            vec![text("very "), el("code", &[], vec![text("deep")])],
+                        ),
+                        text(" text"),
+                    ],
+                )],
+            })
+        }
+
+        #[test]
+        fn attributes() {
+            run(&Case {
+                input: r#"<a href="/2023/about" target=_blank data-x='1 &amp; 2' hidden>x</a>"#,
+                expected: vec![el(
+                    "a",
+                    &[
+                        ("href", "/2023/about"),
+                        ("target", "_blank"),
+                        ("data-x", "1 & 2"),
+                        ("hidden", ""),
+                    ],
+                    vec![text("x")],
+                )],
+            })
+        }
+
+        #[test]
+        fn entities() {
+            run(&Case {
+                input: "<p>&lt;a&gt; &amp; &mdash; &eacute; &#8230; &#x2014; &nbsp;&bogus;</p>",
+                expected: vec![el(
+                    "p",
+                    &[],
+                    vec![text(
+                        "<a> & \u{2014} \u{e9} \u{2026} \u{2014} \u{a0}&bogus;",
+                    )],
+                )],
+            })
+        }
+
+        #[test]
+        fn comments_and_doctype() {
+            run(&Case {
+                input: "<!DOCTYPE html><!-- <p>hidden</p> --><p>shown</p>",
+                expected: vec![el("p", &[], vec![text("shown")])],
+            })
+        }
+
+        #[test]
+        fn void_and_self_closing() {
+            run(&Case {
+                input: "<p>a<br>b<span/>c</p>",
+                expected: vec![el(
+                    "p",
+                    &[],
+                    vec![
+                        text("a"),
+                        el("br", &[], vec![]),
+                        text("b"),
+                        el("span", &[], vec![]),
+                        text("c"),
+                    ],
+                )],
+            })
+        }
+
+        #[test]
+        fn stray_and_unclosed_tags() {
+            run(&Case {
+                input: "</div><p>a</span><em>b",
+                expected: vec![el(
+                    "p",
+                    &[],
+                    vec![text("a"), el("em", &[], vec![text("b")])],
+                )],
+            })
+        }
+
+        #[test]
+        fn implicitly_closed_list_items() {
+            run(&Case {
+                input: "<ul><li>a<li>b<ul><li>c</ul></ul>",
+                expected: vec![el(
+                    "ul",
+                    &[],
+                    vec![
+                        el("li", &[], vec![text("a")]),
+                        el(
+                            "li",
+                            &[],
+                            vec![
+                                text("b"),
+                                el("ul", &[], vec![el("li", &[], vec![text("c")])]),
+                            ],
+                        ),
+                    ],
+                )],
+            })
+        }
+
+        #[test]
+        fn raw_text() {
+            run(&Case {
+                input: "<script>if (a < b) { x = '</p>'; }</script><p>x</p>",
+                expected: vec![
+                    el("script", &[], vec![text("if (a < b) { x = '</p>'; }")]),
+                    el("p", &[], vec![text("x")]),
+                ],
+            })
+        }
+
+        #[test]
+        fn lone_angle_bracket() {
+            run(&Case {
+                input: "<p>a < b</p>",
+                expected: vec![el("p", &[], vec![text("a < b")])],
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, parse(test.input))
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Reading the requests which reach a stand-in for adventofcode.com, shared
+//! by the `aoc_stand_in` binary and the single-shot server in unit tests.
+
+use std::io::{self, BufRead};
+
+/// A request as received by a stand-in.
+#[derive(Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    /// Reads a request line, headers and a `Content-Length` body.
+    pub fn read(reader: &mut impl BufRead) -> io::Result<Self> {
+        let invalid = |reason: &str| io::Error::new(io::ErrorKind::InvalidData, reason);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut request_line = request_line.split_whitespace();
+        let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+            return Err(invalid("malformed request line"));
+        };
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (k, v) = line
+                .split_once(':')
+                .ok_or_else(|| invalid("malformed header"))?;
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+
+        let mut request = Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            headers,
+            body: String::new(),
+        };
+        let length = request
+            .header("content-length")
+            .map_or(Ok(0), str::parse)
+            .map_err(|_| invalid("malformed Content-Length"))?;
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        request.body = String::from_utf8(body).map_err(|_| invalid("body isn't UTF-8"))?;
+
+        Ok(request)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
@@ -0,0 +1,471 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use toml_edit::{Document, Item, Value};
+
+use crate::{
+    data,
+    error::{Error, WithPath},
+};
+
+#[derive(Args, Debug)]
+pub struct MigrateOpts {
+    #[arg(
+        value_parser = clap::value_parser!(u32).range(2015..),
+        help = "Year the existing days belong to"
+    )]
+    pub year: u32,
+}
+
+/// Directory holding a year's inputs, e.g. `data/2023`.
+pub fn data_dir(year: u32) -> PathBuf {
+    Path::new(data::DATA_DIR).join(year.to_string())
+}
+
+/// Directory holding a year's days in one language, e.g. `rs/y2023`.
+///
+/// The `y` keeps the folder a valid identifier in languages whose package
+/// paths follow the directory structure.
+pub fn year_dir(lang_dir: &str, year: u32) -> PathBuf {
+    Path::new(lang_dir).join(format!("y{year}"))
+}
+
+/// Name of a day's Rust crate, e.g. `y2023_day_05`. Package names must be
+/// unique across the workspace, so they include the year.
+pub fn crate_name(year: u32, day: u32) -> String {
+    format!("y{year}_day_{day:02}")
+}
+
+/// Years with a directory of inputs under `data_root`, sorted.
+pub fn years(data_root: &Path) -> Result<Vec<u32>, Error> {
+    let mut years = Vec::new();
+    if !data_root.is_dir() {
+        return Ok(years);
+    }
+    for entry in fs::read_dir(data_root).with_path(data_root)? {
+        let entry = entry.with_path(data_root)?;
+        if !entry.path().is_dir() {
+            continue;
+        }
+        if let Ok(year) = entry.file_name().to_string_lossy().parse() {
+            years.push(year);
+        }
+    }
+    years.sort();
+    Ok(years)
+}
+
+/// Moves a single-year layout under `root` (`data/day_05.txt`, `rs/day_05`)
+/// to the year-qualified one (`data/2023/day_05.txt`, `rs/y2023/day_05`),
+/// returning a line per change.
+///
+/// Rust crates are renamed to their year-qualified name, their data paths
+/// updated, and the workspace manifest pointed at the new directories. The
+/// data paths in other languages' day folders are updated too. Nothing is
+/// moved if any destination already exists.
+pub fn migrate(root: &Path, year: u32, lang_dirs: &[&str]) -> Result<Vec<String>, Error> {
+    let data_root = root.join(data::DATA_DIR);
+    let data_year = data_root.join(year.to_string());
+
+    let mut moves = Vec::new();
+    if data_root.is_dir() {
+        for file in data::encrypted_files(&data_root)?
+            .into_iter()
+            .chain(data::plaintext_files(&data_root)?)
+        {
+            if data::day_of(&file).is_some() {
+                let to = data_year.join(file.file_name().unwrap_or_default());
+                moves.push((file, to));
+            }
+        }
+    }
+    for lang_dir in lang_dirs {
+        let from_dir = root.join(lang_dir);
+        if !from_dir.is_dir() {
+            continue;
+        }
+        let to_dir = root.join(year_dir(lang_dir, year));
+        for entry in fs::read_dir(&from_dir).with_path(&from_dir)? {
+            let entry = entry.with_path(&from_dir)?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() && day_number(&name).is_some() {
+                moves.push((entry.path(), to_dir.join(name)));
+            }
+        }
+    }
+    moves.sort();
+
+    if let Some((_, to)) = moves.iter().find(|(_, to)| to.exists()) {
+        return Err(Error::Usage(format!(
+            "{} already exists; nothing was moved",
+            to.display()
+        )));
+    }
+
+    let mut changes = Vec::new();
+    for (from, to) in &moves {
+        fs::create_dir_all(to.parent().unwrap_or(root)).with_path(to)?;
+        fs::rename(from, to).with_path(from)?;
+        changes.push(format!(
+            "Moved {} to {}",
+            display(root, from),
+            display(root, to)
+        ));
+    }
+
+    let crates_dir = root.join(year_dir("rs", year));
+    for (_, to) in moves.iter().filter(|(_, to)| !to.starts_with(&data_year)) {
+        let name = to.file_name().unwrap_or_default().to_string_lossy();
+        let Some(day) = day_number(&name) else {
+            continue;
+        };
+        if to.starts_with(&crates_dir) {
+            rename_crate(to, year, day)?;
+            changes.push(format!("Renamed crate {name} to {}", crate_name(year, day)));
+        } else if update_data_paths(to, year, day)? {
+            changes.push(format!("Updated the data paths in {}", display(root, to)));
+        }
+    }
+
+    let manifest_path = root.join("Cargo.toml");
+    if manifest_path.is_file() {
+        let manifest = fs::read_to_string(&manifest_path).with_path(&manifest_path)?;
+        let updated = migrate_manifest(&manifest, year).map_err(|err| Error::Manifest {
+            path: manifest_path.clone(),
+            reason: err.to_string(),
+        })?;
+        if updated != manifest {
+            fs::write(&manifest_path, updated).with_path(&manifest_path)?;
+            changes.push("Updated the workspace members in Cargo.toml".to_string());
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Day number of a `day_05` folder.
+fn day_number(name: &str) -> Option<u32> {
+    name.strip_prefix("day_")?.parse().ok()
+}
+
+fn display(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// Renames the crate in `dir` from `day_05` to its year-qualified name, and
+/// points its data paths at the year's directory, now one level further up.
+fn rename_crate(dir: &Path, year: u32, day: u32) -> Result<(), Error> {
+    let old = format!("day_{day:02}");
+    let new = crate_name(year, day);
+
+    let manifest_path = dir.join("Cargo.toml");
+    if manifest_path.is_file() {
+        let manifest = fs::read_to_string(&manifest_path).with_path(&manifest_path)?;
+        fs::write(
+            &manifest_path,
+            manifest.replacen(
+                &format!("name = \"{old}\""),
+                &format!("name = \"{new}\""),
+                1,
+            ),
+        )
+        .with_path(&manifest_path)?;
+    }
+
+    let bin = dir.join("src").join("bin");
+    if bin.join(format!("{old}.rs")).is_file() {
+        fs::rename(bin.join(format!("{old}.rs")), bin.join(format!("{new}.rs")))
+            .with_path(bin.join(format!("{old}.rs")))?;
+    }
+
+    for file in source_files(dir, &["rs"])? {
+        let source = fs::read_to_string(&file).with_path(&file)?;
+        let updated = source
+            .replace(&format!("{old}::"), &format!("{new}::"))
+            .replace("\"../../data\"", "\"../../../data\"")
+            .replace(
+                &format!(".join(\"{old}.txt\")"),
+                &format!(".join(\"{year}\").join(\"{old}.txt\")"),
+            );
+        if updated != source {
+            fs::write(&file, updated).with_path(&file)?;
+        }
+    }
+    Ok(())
+}
+
+/// Points the Go, TypeScript, Python or Zig sources of a moved day folder at
+/// the year's data directory, returning whether any changed.
+fn update_data_paths(dir: &Path, year: u32, day: u32) -> Result<bool, Error> {
+    let file = format!("\"day_{day:02}.txt\"");
+    let rewrites = [
+        // Go tests, run from the day folder
+        (
+            "\"../../data\"".to_string(),
+            "\"../../../data\"".to_string(),
+        ),
+        // Go's filepath.Join and Zig's path.join
+        (format!(", {file}"), format!(", \"{year}\", {file}")),
+        // TypeScript, run from the workspace root
+        (
+            format!("\"./data/day_{day:02}.txt\""),
+            format!("\"./data/{year}/day_{day:02}.txt\""),
+        ),
+        // Python, relative to the module
+        (
+            "parents[2] / \"data\"".to_string(),
+            "parents[3] / \"data\"".to_string(),
+        ),
+        (format!("/ {file}"), format!("/ \"{year}\" / {file}")),
+    ];
+
+    let mut changed = false;
+    for file in source_files(dir, &["go", "ts", "py", "zig"])? {
+        let source = fs::read_to_string(&file).with_path(&file)?;
+        let updated = rewrites.iter().fold(source.clone(), |source, (from, to)| {
+            source.replace(from, to)
+        });
+        if updated != source {
+            fs::write(&file, updated).with_path(&file)?;
+            changed = true;
+        }
+    }
+    Ok(changed)
+}
+
+/// Files under `dir` with one of `extensions`, skipping build output and
+/// installed dependencies.
+fn source_files(dir: &Path, extensions: &[&str]) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_path(dir)? {
+        let path = entry.with_path(dir)?.path();
+        if path.is_dir() {
+            if !["target", "node_modules", "zig-cache", "zig-out"]
+                .iter()
+                .any(|skipped| path.ends_with(skipped))
+            {
+                files.extend(source_files(&path, extensions)?);
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| extensions.iter().any(|wanted| ext == *wanted))
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Points the workspace's `rs/*` members glob and `rs/day_05` default members
+/// at the year-qualified directories.
+fn migrate_manifest(manifest: &str, year: u32) -> Result<String, toml_edit::TomlError> {
+    let mut doc = manifest.parse::<Document>()?;
+    let Some(workspace) = doc.get_mut("workspace").and_then(Item::as_table_mut) else {
+        return Ok(manifest.to_string());
+    };
+
+    for key in ["members", "default-members"] {
+        let Some(array) = workspace.get_mut(key).and_then(Item::as_array_mut) else {
+            continue;
+        };
+        for value in array.iter_mut() {
+            let replacement = match value.as_str() {
+                Some("rs/*") => "rs/*/*".to_string(),
+                Some(member) => match member.strip_prefix("rs/") {
+                    Some(name) if day_number(name).is_some() => {
+                        format!("{}/{name}", year_dir("rs", year).display())
+                    }
+                    _ => continue,
+                },
+                None => continue,
+            };
+            let decor = value.decor().clone();
+            *value = Value::from(replacement);
+            *value.decor_mut() = decor;
+        }
+    }
+
+    Ok(doc.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn migrate_single_year_layout() {
+        let root = TempDir::new("migrate");
+        let files = [
+            (
+                "Cargo.toml",
+                "[workspace]\ndefault-members = [\"rs/day_05\"] # sorted\nmembers = [\"fetch_day\", \"rs/*\"]\n",
+            ),
+            ("data/day_05.txt", "5"),
+            ("data/day_05.enc.txt", "v1$"),
+            ("go/day_05/main.go", "package main"),
+            (
+                "rs/day_05/Cargo.toml",
+                "[package]\nname = \"day_05\"\nversion = \"0.1.0\"\n",
+            ),
+            (
+                "rs/day_05/src/lib.rs",
+                "pub fn read_data(data_dir: &str) -> String {\n    read_to_string(Path::new(data_dir).join(\"day_05.txt\"))\n}\n\nconst DATA_DIR: &str = \"../../data\";\n",
+            ),
+            ("rs/day_05/src/bin/day_05.rs", "use day_05::{read_data, Input};\n"),
+            ("rs/day_05/benches/bench.rs", "use day_05::{read_data, Input};\n"),
+        ];
+        for (file, contents) in files {
+            fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            fs::write(root.join(file), contents).unwrap();
+        }
+
+        let changes = migrate(&root, 2023, &["go", "rs", "ts"]).unwrap();
+
+        assert_eq!(
+            vec![
+                "Moved data/day_05.enc.txt to data/2023/day_05.enc.txt",
+                "Moved data/day_05.txt to data/2023/day_05.txt",
+                "Moved go/day_05 to go/y2023/day_05",
+                "Moved rs/day_05 to rs/y2023/day_05",
+                "Renamed crate day_05 to y2023_day_05",
+                "Updated the workspace members in Cargo.toml",
+            ],
+            changes
+        );
+        let read = |file: &str| fs::read_to_string(root.join(file)).unwrap();
+        assert_eq!(
+            "[workspace]\ndefault-members = [\"rs/y2023/day_05\"] # sorted\nmembers = [\"fetch_day\", \"rs/*/*\"]\n",
+            read("Cargo.toml")
+        );
+        assert_eq!("5", read("data/2023/day_05.txt"));
+        assert_eq!("package main", read("go/y2023/day_05/main.go"));
+        assert_eq!(
+            "[package]\nname = \"y2023_day_05\"\nversion = \"0.1.0\"\n",
+            read("rs/y2023/day_05/Cargo.toml")
+        );
+        assert_eq!(
+            "pub fn read_data(data_dir: &str) -> String {\n    read_to_string(Path::new(data_dir).join(\"2023\").join(\"day_05.txt\"))\n}\n\nconst DATA_DIR: &str = \"../../../data\";\n",
+            read("rs/y2023/day_05/src/lib.rs")
+        );
+        assert_eq!(
+            "use y2023_day_05::{read_data, Input};\n",
+            read("rs/y2023/day_05/src/bin/y2023_day_05.rs")
+        );
+        assert_eq!(
+            "use y2023_day_05::{read_data, Input};\n",
+            read("rs/y2023/day_05/benches/bench.rs")
+        );
+        assert!(!root.join("rs/day_05").exists());
+
+        assert_eq!(
+            Vec::<String>::new(),
+            migrate(&root, 2023, &["go", "rs", "ts"]).unwrap()
+        );
+    }
+
+    #[test]
+    fn migrate_other_languages() {
+        let root = TempDir::new("migrate_other_languages");
+        let files = [
+            (
+                "go/day_05/main.go",
+                "contents, err := os.ReadFile(filepath.Join(dir, \"day_05.txt\"))\n",
+            ),
+            (
+                "go/day_05/main_test.go",
+                "var contents = readData(\"../../data\")\n",
+            ),
+            (
+                "ts/day_05/day.ts",
+                "return Deno.readTextFileSync(\"./data/day_05.txt\")\n",
+            ),
+            (
+                "py/day_05/day.py",
+                "DATA_DIR = Path(__file__).resolve().parents[2] / \"data\"\nreturn (data_dir / \"day_05.txt\").read_text()\n",
+            ),
+            (
+                "zig/day_05/main.zig",
+                "const path = try std.fs.path.join(allocator, &.{ dir, \"day_05.txt\" });\n",
+            ),
+            ("ts/day_05/node_modules/dep/index.ts", "\"./data/day_05.txt\""),
+        ];
+        for (file, contents) in files {
+            fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            fs::write(root.join(file), contents).unwrap();
+        }
+
+        let changes = migrate(&root, 2023, &["go", "py", "ts", "zig"]).unwrap();
+
+        assert_eq!(
+            vec![
+                "Moved go/day_05 to go/y2023/day_05",
+                "Moved py/day_05 to py/y2023/day_05",
+                "Moved ts/day_05 to ts/y2023/day_05",
+                "Moved zig/day_05 to zig/y2023/day_05",
+                "Updated the data paths in go/y2023/day_05",
+                "Updated the data paths in py/y2023/day_05",
+                "Updated the data paths in ts/y2023/day_05",
+                "Updated the data paths in zig/y2023/day_05",
+            ],
+            changes
+        );
+        let read = |file: &str| fs::read_to_string(root.join(file)).unwrap();
+        assert_eq!(
+            "contents, err := os.ReadFile(filepath.Join(dir, \"2023\", \"day_05.txt\"))\n",
+            read("go/y2023/day_05/main.go")
+        );
+        assert_eq!(
+            "var contents = readData(\"../../../data\")\n",
+            read("go/y2023/day_05/main_test.go")
+        );
+        assert_eq!(
+            "return Deno.readTextFileSync(\"./data/2023/day_05.txt\")\n",
+            read("ts/y2023/day_05/day.ts")
+        );
+        assert_eq!(
+            "DATA_DIR = Path(__file__).resolve().parents[3] / \"data\"\nreturn (data_dir / \"2023\" / \"day_05.txt\").read_text()\n",
+            read("py/y2023/day_05/day.py")
+        );
+        assert_eq!(
+            "const path = try std.fs.path.join(allocator, &.{ dir, \"2023\", \"day_05.txt\" });\n",
+            read("zig/y2023/day_05/main.zig")
+        );
+        assert_eq!(
+            "\"./data/day_05.txt\"",
+            read("ts/y2023/day_05/node_modules/dep/index.ts")
+        );
+    }
+
+    #[test]
+    fn migrate_refuses_to_overwrite() {
+        let root = TempDir::new("migrate_overwrite");
+        fs::create_dir_all(root.join("data/2023")).unwrap();
+        fs::write(root.join("data/day_05.txt"), "new").unwrap();
+        fs::write(root.join("data/2023/day_05.txt"), "old").unwrap();
+
+        assert!(migrate(&root, 2023, &["rs"]).is_err());
+        assert_eq!(
+            "new",
+            fs::read_to_string(root.join("data/day_05.txt")).unwrap()
+        );
+    }
+
+    #[test]
+    fn years_with_data() {
+        let root = TempDir::new("years");
+        for dir in ["2023", "2022", "scratch"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("2021"), "").unwrap();
+
+        assert_eq!(vec![2022, 2023], years(&root).unwrap());
+    }
+}
@@ -0,0 +1,589 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Display, Write},
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use clap::Args;
+use serde::Deserialize;
+
+use crate::{
+    calendar::{self, Clock},
+    client::{AocClient, FetchError},
+    error::Error,
+};
+
+/// Where fetched leaderboards are kept between runs.
+pub const CACHE_DIR: &str = ".cache/leaderboard";
+
+/// AoC asks for the leaderboard API to be polled at most once every 15 minutes.
+const CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Args, Debug)]
+pub struct LeaderboardOpts {
+    #[arg(
+        long,
+        env = "AOC_LEADERBOARD_ID",
+        help = "ID of the private leaderboard, as in its URL"
+    )]
+    pub id: u64,
+}
+
+#[derive(Debug)]
+pub enum LeaderboardError {
+    Fetch(FetchError),
+    /// Reading or writing the cached leaderboard at `path`.
+    Io {
+        path: PathBuf,
+        err: std::io::Error,
+    },
+    /// `source` is the URL or cached file the JSON came from.
+    Parse {
+        source: String,
+        err: serde_json::Error,
+    },
+}
+
+impl Display for LeaderboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LeaderboardError::Fetch(err) => err.fmt(f),
+            LeaderboardError::Io { path, err } => {
+                write!(f, "leaderboard cache {}: {err}", path.display())
+            }
+            LeaderboardError::Parse { source, err } => {
+                write!(f, "unexpected leaderboard JSON in {source}: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LeaderboardError {}
+
+impl From<FetchError> for LeaderboardError {
+    fn from(err: FetchError) -> Self {
+        LeaderboardError::Fetch(err)
+    }
+}
+
+/// Maps an io error on the cached leaderboard at `path`.
+fn cache_error(path: &Path) -> impl FnOnce(std::io::Error) -> LeaderboardError + '_ {
+    move |err| LeaderboardError::Io {
+        path: path.to_path_buf(),
+        err,
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Leaderboard {
+    pub members: HashMap<String, Member>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u32,
+    pub last_star_ts: i64,
+    /// Day -> part -> when the star was earned.
+    pub completion_day_level: BTreeMap<u32, BTreeMap<u32, Star>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Star {
+    pub get_star_ts: i64,
+}
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    fn star(&self, day: u32, part: u32) -> Option<&Star> {
+        self.completion_day_level.get(&day)?.get(&part)
+    }
+}
+
+impl Leaderboard {
+    /// Members by local score, ties going to whoever got there first.
+    pub fn ranked(&self) -> Vec<&Member> {
+        let mut members = self.members.values().collect::<Vec<_>>();
+        members.sort_by_key(|member| {
+            (
+                std::cmp::Reverse(member.local_score),
+                member.last_star_ts,
+                member.id,
+            )
+        });
+        members
+    }
+
+    fn last_day(&self) -> u32 {
+        self.members
+            .values()
+            .flat_map(|member| member.completion_day_level.keys())
+            .copied()
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+/// The latest leaderboard, and the one cached before it.
+#[derive(Debug)]
+pub struct Snapshots {
+    pub current: Leaderboard,
+    pub previous: Option<Leaderboard>,
+}
+
+/// Loads the leaderboard from the cache, fetching it again once the cached
+/// copy is older than 15 minutes. The replaced copy becomes the previous
+/// snapshot.
+pub fn load(
+    client: &AocClient,
+    clock: &impl Clock,
+    cache_dir: &Path,
+    year: u32,
+    id: u64,
+) -> Result<Snapshots, LeaderboardError> {
+    let current_path = cache_dir.join(format!("{year}_{id}.json"));
+    let previous_path = cache_dir.join(format!("{year}_{id}.previous.json"));
+
+    let fresh = match fs::metadata(&current_path).and_then(|meta| meta.modified()) {
+        Ok(modified) => (clock.now() - DateTime::<Utc>::from(modified))
+            .to_std()
+            .is_ok_and(|age| age < CACHE_TTL),
+        Err(_) => false,
+    };
+
+    if !fresh {
+        let url = client.url(&format!("/{year}/leaderboard/private/view/{id}.json"));
+        let json = client.get(&url)?;
+        parse(&url, &json)?;
+
+        fs::create_dir_all(cache_dir).map_err(cache_error(cache_dir))?;
+        if current_path.exists() {
+            fs::rename(&current_path, &previous_path).map_err(cache_error(&previous_path))?;
+        }
+        fs::write(&current_path, json).map_err(cache_error(&current_path))?;
+    }
+
+    Ok(Snapshots {
+        current: read(&current_path)?,
+        previous: if previous_path.exists() {
+            Some(read(&previous_path)?)
+        } else {
+            None
+        },
+    })
+}
+
+fn read(path: &Path) -> Result<Leaderboard, LeaderboardError> {
+    parse(
+        &path.display().to_string(),
+        &fs::read_to_string(path).map_err(cache_error(path))?,
+    )
+}
+
+fn parse(source: &str, json: &str) -> Result<Leaderboard, LeaderboardError> {
+    serde_json::from_str(json).map_err(|err| LeaderboardError::Parse {
+        source: source.to_string(),
+        err,
+    })
+}
+
+/// Ranked table of stars per day: `*` for both parts, `+` for part 1 only.
+pub fn format_table(board: &Leaderboard) -> String {
+    let days = 1..=board.last_day();
+    let mut output = String::new();
+
+    if *days.end() >= 10 {
+        let tens = days
+            .clone()
+            .map(|day| match day / 10 {
+                0 => ' ',
+                tens => char::from_digit(tens, 10).unwrap(),
+            })
+            .collect::<String>();
+        writeln!(output, "{:19}{tens}", "").unwrap();
+    }
+    let units = days
+        .clone()
+        .map(|day| char::from_digit(day % 10, 10).unwrap())
+        .collect::<String>();
+    writeln!(
+        output,
+        "{:>3}  {:>5}  {:>5}  {units}  {:<23}  Name",
+        "#", "Score", "Stars", "Last star"
+    )
+    .unwrap();
+
+    for (rank, member) in board.ranked().into_iter().enumerate() {
+        let stars = days
+            .clone()
+            .map(|day| match (member.star(day, 1), member.star(day, 2)) {
+                (_, Some(_)) => '*',
+                (Some(_), None) => '+',
+                (None, None) => '.',
+            })
+            .collect::<String>();
+        let last_star = match member.stars {
+            0 => String::new(),
+            _ => timestamp(member.last_star_ts),
+        };
+        writeln!(
+            output,
+            "{:>3}  {:>5}  {:>5}  {stars}  {last_star:<23}  {}",
+            rank + 1,
+            member.local_score,
+            member.stars,
+            member.display_name()
+        )
+        .unwrap();
+    }
+
+    output
+}
+
+/// When each member finished the parts of `day`, relative to it unlocking.
+pub fn format_day(board: &Leaderboard, year: u32, day: u32) -> Result<String, Error> {
+    let unlock = calendar::unlock_time(year, day)?.timestamp();
+    let since_unlock = |star: Option<&Star>| {
+        star.map(|star| {
+            calendar::format_duration(Duration::from_secs(
+                star.get_star_ts.saturating_sub(unlock).max(0) as u64,
+            ))
+        })
+    };
+
+    let mut finishers = board
+        .members
+        .values()
+        .filter(|member| member.star(day, 1).is_some())
+        .collect::<Vec<_>>();
+    finishers.sort_by_key(|member| {
+        (
+            member
+                .star(day, 2)
+                .map_or(i64::MAX, |star| star.get_star_ts),
+            member
+                .star(day, 1)
+                .map_or(i64::MAX, |star| star.get_star_ts),
+            member.id,
+        )
+    });
+
+    let mut output = format!("Day {day}\n");
+    if finishers.is_empty() {
+        output.push_str("  No stars yet\n");
+    }
+    let width = finishers
+        .iter()
+        .map(|member| member.display_name().len())
+        .max()
+        .unwrap_or(0);
+    for member in finishers {
+        write!(
+            output,
+            "  {:<width$}  part 1 {}",
+            member.display_name(),
+            since_unlock(member.star(day, 1)).unwrap_or_default()
+        )
+        .unwrap();
+        if let Some(part_2) = since_unlock(member.star(day, 2)) {
+            write!(output, "  part 2 {part_2}").unwrap();
+        }
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Stars, points and places gained since the `previous` snapshot.
+pub fn format_changes(previous: &Leaderboard, current: &Leaderboard) -> String {
+    let previous_ranks = previous
+        .ranked()
+        .into_iter()
+        .enumerate()
+        .map(|(rank, member)| (member.id, rank + 1))
+        .collect::<HashMap<_, _>>();
+    let previous_members = previous
+        .members
+        .values()
+        .map(|member| (member.id, member))
+        .collect::<HashMap<_, _>>();
+
+    let mut output = String::new();
+    for (rank, member) in current.ranked().into_iter().enumerate() {
+        let rank = rank + 1;
+        let Some(before) = previous_members.get(&member.id) else {
+            writeln!(
+                output,
+                "  {}: joined with {} {}",
+                member.display_name(),
+                member.stars,
+                plural(member.stars, "star")
+            )
+            .unwrap();
+            continue;
+        };
+
+        let mut changes = Vec::new();
+
+        let new_stars = member
+            .completion_day_level
+            .iter()
+            .flat_map(|(day, parts)| parts.keys().map(move |part| (*day, *part)))
+            .filter(|(day, part)| before.star(*day, *part).is_none())
+            .map(|(day, part)| format!("day {day} part {part}"))
+            .collect::<Vec<_>>();
+        if !new_stars.is_empty() {
+            changes.push(format!(
+                "+{} {} ({})",
+                new_stars.len(),
+                plural(new_stars.len() as u32, "star"),
+                new_stars.join(", ")
+            ));
+        }
+
+        let points = i64::from(member.local_score) - i64::from(before.local_score);
+        if points != 0 {
+            changes.push(format!(
+                "{points:+} {}",
+                plural(points.unsigned_abs() as u32, "point")
+            ));
+        }
+
+        let previous_rank = previous_ranks[&member.id];
+        if previous_rank != rank {
+            changes.push(format!("rank {previous_rank} -> {rank}"));
+        }
+
+        if !changes.is_empty() {
+            writeln!(
+                output,
+                "  {}: {}",
+                member.display_name(),
+                changes.join(", ")
+            )
+            .unwrap();
+        }
+    }
+
+    if output.is_empty() {
+        "No changes since the previous snapshot\n".to_string()
+    } else {
+        format!("Since the previous snapshot:\n{output}")
+    }
+}
+
+fn plural(count: u32, noun: &str) -> String {
+    if count == 1 {
+        noun.to_string()
+    } else {
+        format!("{noun}s")
+    }
+}
+
+fn timestamp(secs: i64) -> String {
+    DateTime::<Utc>::from_timestamp(secs, 0)
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calendar::manual::ManualClock, client::stand_in, test_util::TempDir};
+
+    fn fixture(name: &str) -> Leaderboard {
+        let json = match name {
+            "previous" => include_str!("../fixtures/leaderboard/previous.json"),
+            "current" => include_str!("../fixtures/leaderboard/current.json"),
+            _ => unreachable!(),
+        };
+        parse(name, json).unwrap()
+    }
+
+    #[test]
+    fn table() {
+        assert_eq!(
+            "  #  Score  Stars  123  Last star                Name
+  1     20      6  ***  2023-12-03 05:15:00 UTC  alice
+  2     14      5  **+  2023-12-03 05:23:20 UTC  bob
+  3      5      2  *..  2023-12-02 06:56:40 UTC  carol
+  4      2      1  +..  2023-12-01 05:53:20 UTC  (anonymous user #3)
+",
+            format_table(&fixture("current"))
+        )
+    }
+
+    #[test]
+    fn table_header_past_day_9() {
+        let mut board = fixture("previous");
+        let alice = board.members.get_mut("1").unwrap();
+        alice
+            .completion_day_level
+            .insert(12, BTreeMap::from([(1, Star { get_star_ts: 0 })]));
+
+        let table = format_table(&board);
+        let mut lines = table.lines();
+
+        assert_eq!(
+            Some(format!("{:19}         111", "").as_str()),
+            lines.next()
+        );
+        assert_eq!(
+            Some("  #  Score  Stars  123456789012  Last star                Name"),
+            lines.next()
+        );
+        assert_eq!(
+            Some("  1     11      4  **.........+  2023-12-02 05:25:00 UTC  alice"),
+            lines.next()
+        );
+    }
+
+    mod day {
+        use super::*;
+
+        struct Case {
+            day: u32,
+            expected: &'static str,
+        }
+
+        #[test]
+        fn both_parts() {
+            run(&Case {
+                day: 3,
+                expected: "Day 3
+  alice  part 1 00:06:40  part 2 00:15:00
+  bob    part 1 00:23:20
+",
+            })
+        }
+
+        #[test]
+        fn over_a_day() {
+            run(&Case {
+                day: 1,
+                expected: "Day 1
+  alice                part 1 00:05:00  part 2 00:10:00
+  bob                  part 1 00:06:40  part 2 00:20:00
+  carol                part 1 1d 01:55:00  part 2 1d 01:56:40
+  (anonymous user #3)  part 1 00:53:20
+",
+            })
+        }
+
+        #[test]
+        fn no_stars() {
+            run(&Case {
+                day: 4,
+                expected: "Day 4\n  No stars yet\n",
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected,
+                format_day(&fixture("current"), 2023, test.day).unwrap()
+            )
+        }
+    }
+
+    #[test]
+    fn changes() {
+        assert_eq!(
+            "Since the previous snapshot:
+  alice: +2 stars (day 3 part 1, day 3 part 2), +9 points
+  bob: +2 stars (day 2 part 2, day 3 part 1), +6 points
+  carol: joined with 2 stars
+  (anonymous user #3): +1 point, rank 3 -> 4
+",
+            format_changes(&fixture("previous"), &fixture("current"))
+        )
+    }
+
+    #[test]
+    fn no_changes() {
+        assert_eq!(
+            "No changes since the previous snapshot\n",
+            format_changes(&fixture("current"), &fixture("current"))
+        )
+    }
+
+    /// Cache directory under the system temp dir, optionally holding a
+    /// cached leaderboard.
+    fn cache_dir(name: &str, cached: Option<&str>) -> TempDir {
+        let dir = TempDir::new(name);
+        if let Some(cached) = cached {
+            fs::write(dir.join("2023_42.json"), cached).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn load_uses_fresh_cache() {
+        let dir = cache_dir(
+            "leaderboard_fresh",
+            Some(include_str!("../fixtures/leaderboard/current.json")),
+        );
+        let clock = ManualClock::at(&(Utc::now() + chrono::Duration::minutes(14)).to_rfc3339());
+        // Nothing listens here, so any request would fail
+        let client = AocClient::new("http://127.0.0.1:1", "session");
+
+        let snapshots = load(&client, &clock, &dir, 2023, 42).unwrap();
+
+        assert_eq!(4, snapshots.current.members.len());
+        assert!(snapshots.previous.is_none());
+    }
+
+    #[test]
+    fn load_names_unreadable_cache() {
+        let dir = cache_dir("leaderboard_unreadable", None);
+        let cached = dir.join("2023_42.json");
+        fs::create_dir(&cached).unwrap();
+        let clock = ManualClock::at(&Utc::now().to_rfc3339());
+        let client = AocClient::new("http://127.0.0.1:1", "session");
+
+        let err = load(&client, &clock, &dir, 2023, 42).unwrap_err();
+
+        assert!(
+            matches!(&err, LeaderboardError::Io { path, .. } if *path == cached),
+            "{err:?}"
+        );
+        assert!(
+            err.to_string().contains(&cached.display().to_string()),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn load_refreshes_stale_cache() {
+        let dir = cache_dir(
+            "leaderboard_stale",
+            Some(include_str!("../fixtures/leaderboard/previous.json")),
+        );
+        let clock = ManualClock::at(&(Utc::now() + chrono::Duration::minutes(16)).to_rfc3339());
+        let (host, request) =
+            stand_in::serve_once(200, include_str!("../fixtures/leaderboard/current.json"));
+        let client = AocClient::new(host, "session");
+
+        let snapshots = load(&client, &clock, &dir, 2023, 42).unwrap();
+
+        let request = request.join().unwrap();
+        assert_eq!("/2023/leaderboard/private/view/42.json", request.path);
+        assert_eq!(Some("session=session"), request.header("cookie"));
+        assert_eq!(4, snapshots.current.members.len());
+        assert_eq!(3, snapshots.previous.unwrap().members.len());
+        assert_eq!(
+            include_str!("../fixtures/leaderboard/previous.json"),
+            fs::read_to_string(dir.join("2023_42.previous.json")).unwrap()
+        );
+    }
+}
@@ -1,31 +1,79 @@
-use std::{fmt::Display, fs, iter::Peekable, path::Path, str::Chars};
-
-use aes_gcm::{
-    aead::{rand_core::RngCore, Aead, OsRng},
-    Aes256Gcm, Key, KeyInit, Nonce,
+use std::{
+    fmt::Display,
+    fs,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
 };
-use base64::Engine;
-use chrono::{Datelike, Utc};
-use clap::{Parser, ValueEnum};
-use regex::Regex;
-use reqwest::Error;
-use sailfish::TemplateOnce;
 
-type AnyResult<T> = Result<T, Box<dyn std::error::Error>>;
+use aes_gcm::aead::{rand_core::RngCore, OsRng};
+use calendar::SystemClock;
+use chrono::{Datelike, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use client::{AocClient, FetchError, AOC_URL};
+use credentials::{Credentials, LoginOpts};
+use crypto::DataKey;
+use data::DecryptTarget;
+use days::DaySet;
+use error::{Error, WithPath};
+use example::Example;
+use files::{FileWriter, OnConflict};
+use layout::MigrateOpts;
+use leaderboard::LeaderboardOpts;
+use scaffold::{Scaffold, Vars};
+use show::ShowOpts;
+use status::StatusOpts;
+use submit::SubmitOpts;
+use template_dir::TemplateDir;
+
+mod calendar;
+mod client;
+mod credentials;
+mod crypto;
+mod data;
+mod days;
+mod error;
+mod example;
+mod files;
+mod html;
+#[cfg(test)]
+mod http_request;
+mod layout;
+mod leaderboard;
+mod markdown;
+mod readme;
+mod scaffold;
+mod show;
+mod status;
+mod submit;
+mod template_dir;
+#[cfg(test)]
+mod test_util;
+mod workspace;
 
 #[derive(clap::Parser, Debug)]
+#[command(after_help = error::EXIT_CODES)]
 struct Opts {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(
         short,
         long = "day",
-        value_name = "DAY",
-        value_parser = clap::value_parser!(u32).range(1..=25),
-        default_value_t = Utc::now().with_timezone(&chrono::offset::FixedOffset::west_opt(5 * 3600).unwrap()).day().min(25),
-        help = "Day to download the instructions and input for (defaults to the min(current day, 25) in EST)"
+        value_name = "DAYS",
+        global = true,
+        help = "Days to download the instructions and input for, or with '--decrypt-data' to decrypt, e.g. '5', '1..=11' or '3,5,9' (defaults to the min(current day, 25) in EST, or every day when decrypting)"
     )]
-    day: u32,
+    day: Option<DaySet>,
 
-    #[arg(long, default_value_t = Utc::now().year() as u32, help = "Competition year")]
+    #[arg(
+        long,
+        global = true,
+        default_value_t = Utc::now().year() as u32,
+        value_parser = clap::value_parser!(u32).range(2015..),
+        help = "Competition year"
+    )]
     year: u32,
 
     #[arg(
@@ -41,6 +89,36 @@ struct Opts {
     #[arg(long, group = "data", help = "Only decrypt the input data")]
     decrypt_data: bool,
 
+    #[arg(
+        long,
+        value_name = "DIR",
+        requires = "decrypt_data",
+        conflicts_with = "stdout",
+        help = "Write the decrypted input data to DIR instead of next to the encrypted data"
+    )]
+    output_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "decrypt_data",
+        help = "Write the decrypted input data for a single day to stdout"
+    )]
+    stdout: bool,
+
+    #[arg(
+        long,
+        group = "data",
+        help = "Only (re)create the encrypted input data from the plaintext inputs"
+    )]
+    encrypt_data: bool,
+
+    #[arg(
+        long,
+        group = "data",
+        help = "Only check the encrypted and plaintext input data agree, without writing anything"
+    )]
+    audit_data: bool,
+
     #[arg(
         short,
         long,
@@ -51,39 +129,212 @@ struct Opts {
     )]
     langs: Vec<Lang>,
 
+    #[arg(
+        long,
+        env = "AOC_ENCRYPT_READMES",
+        help = "Also store each README encrypted, as README.enc.md, so the puzzle text can be committed encrypted. With '--encrypt-data', (re)create them from the existing READMEs"
+    )]
+    encrypt_readmes: bool,
+
     #[arg(long, help = "Skip code template creation for each language")]
     skip_templates: bool,
 
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OnConflict::Skip,
+        help = "What to do when a template would replace an existing file with different contents"
+    )]
+    on_conflict: OnConflict,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        env = "AOC_TEMPLATE_DIR",
+        help = "Render the code templates from this directory instead of the built-in ones (defaults to 'template_dir' in [workspace.metadata.fetch_day] of the workspace Cargo.toml, else in ~/.config/fetch_day/config.toml)"
+    )]
+    template_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        global = true,
+        env = "AOC_WORKSPACE_ROOT",
+        help = "Workspace to read and write, instead of the nearest one containing the current directory"
+    )]
+    root: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Overwrite existing files with freshly rendered templates"
+    )]
+    force: bool,
+
+    #[arg(
+        long,
+        help = "List the files that would be created or modified, without writing anything"
+    )]
+    dry_run: bool,
+
     #[arg(long, help = "Keep the raw instruction HTML file")]
     keep_instructions: bool,
 
     #[arg(
         long,
-        help = "Update the READMEs to contain part 2. Alias for '--download --no-data --skip-templates'"
+        help = "Update the READMEs to contain part 2 and fill in the part 2 example answer. Alias for '--download --no-data --skip-templates'"
     )]
     part_2: bool,
+
+    #[arg(
+        long,
+        help = "Wait for the puzzle to unlock, showing a countdown, then fetch it"
+    )]
+    wait: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        global = true,
+        default_value = "3",
+        value_parser = parse_seconds,
+        help = "Minimum time between requests to AoC"
+    )]
+    request_interval: Duration,
+
+    #[arg(
+        long,
+        global = true,
+        env = "AOC_CONTACT",
+        help = "Contact details, e.g. an email address, to include in the User-Agent sent to AoC"
+    )]
+    contact: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        global = true,
+        env = "AOC_BASE_URL",
+        default_value = AOC_URL,
+        help = "AoC host to send requests to, e.g. a local 'aoc_stand_in'"
+    )]
+    base_url: String,
+}
+
+impl Opts {
+    /// `--day`, or today's puzzle if it wasn't given.
+    fn days(&self) -> DaySet {
+        self.day
+            .clone()
+            .unwrap_or_else(|| DaySet::from(calendar::default_day(&SystemClock)))
+    }
+
+    /// The `--day`, for commands which act on a single day.
+    fn single_day(&self) -> Result<u32, Error> {
+        let days = self.days();
+        days.single().ok_or_else(|| {
+            Error::Usage(format!(
+                "'--day {days}' must be a single day for this command"
+            ))
+        })
+    }
+
+    fn client(&self, token: &str) -> AocClient {
+        let client = AocClient::new(&self.base_url, token).with_min_interval(self.request_interval);
+        match &self.contact {
+            Some(contact) => client.with_contact(contact),
+            None => client,
+        }
+    }
+
+    fn file_writer(&self) -> FileWriter {
+        FileWriter {
+            on_conflict: self.on_conflict,
+            force: self.force,
+            dry_run: self.dry_run,
+        }
+    }
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    s.parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| format!("'{s}' is not a number of seconds"))
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Submit an answer for the day and report the verdict
+    Submit(SubmitOpts),
+    /// Re-encrypt every encrypted input and README from 'AOC_AES_KEY' to 'AOC_NEW_AES_KEY'
+    RotateKey,
+    /// Show a private leaderboard, what changed since it was last fetched and, with '--day', that day's finishing times
+    Leaderboard(LeaderboardOpts),
+    /// Report stars and local progress for every day of the calendar
+    Status(StatusOpts),
+    /// Move a single-year layout (`rs/day_05`, `data/day_05.txt`) under a year
+    MigrateLayout(MigrateOpts),
+    /// Check a session token read from stdin and save it to the credentials file
+    Login(LoginOpts),
+    /// Check the session token, reporting its account and when it expires
+    Check,
+    /// Show the day's instructions in the terminal, from those cached when it was fetched
+    Show(ShowOpts),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 enum Lang {
     Go,
+    Py,
     Rs,
     Ts,
+    Zig,
+}
+
+impl Lang {
+    fn scaffold(self) -> &'static Scaffold {
+        match self {
+            Lang::Go => &scaffold::GO,
+            Lang::Py => &scaffold::PY,
+            Lang::Rs => &scaffold::RS,
+            Lang::Ts => &scaffold::TS,
+            Lang::Zig => &scaffold::ZIG,
+        }
+    }
 }
 
 impl Display for Lang {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Lang::Go => f.write_str("go"),
-            Lang::Rs => f.write_str("rs"),
-            Lang::Ts => f.write_str("ts"),
+        f.write_str(self.scaffold().dir)
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            err.exit_status().into()
         }
     }
 }
 
-fn main() -> AnyResult<()> {
+fn run() -> Result<ExitCode, Error> {
+    dotenvy::dotenv().ok();
+
     let opts = {
-        let mut opts = Opts::parse();
+        let mut opts = match Opts::try_parse() {
+            Ok(opts) => opts,
+            // clap would exit with 2, which `submit` uses for a verdict
+            Err(err) if err.use_stderr() => {
+                let _ = err.print();
+                return Ok(error::ExitStatus::Usage.into());
+            }
+            Err(err) => {
+                let _ = err.print();
+                return Ok(ExitCode::SUCCESS);
+            }
+        };
         if opts.part_2 {
             opts.force_download = true;
             opts.no_data = true;
@@ -91,536 +342,628 @@ fn main() -> AnyResult<()> {
         }
         opts
     };
-
-    dotenvy::dotenv().ok();
-
-    let aoc_token = std::env::var("AOC_SESSION_TOKEN").expect("No 'AOC_SESSION_TOKEN' set");
-    let key = std::env::var("AOC_AES_KEY").expect("No 'AOC_AES_KEY' set");
-    let day_url = format!("https://adventofcode.com/{}/day/{}", opts.year, opts.day);
-
-    if opts.decrypt_data {
-        return decrypt_data(key.as_bytes());
-    }
-
-    let day = DayBuilder::new(opts, aoc_token, key, day_url);
-
-    day.write_data_file()?;
-    day.write_instruction_files()?;
-
-    for lang in &day.opts.langs {
-        match lang {
-            Lang::Ts => day.add_ts_template()?,
-            Lang::Go => day.add_go_template()?,
-            Lang::Rs => day.add_rs_template()?,
+    let credentials = Credentials::new(credentials::default_path());
+
+    // Every path below is relative to the workspace root, except those given
+    // on the command line, which are relative to where it was run
+    let cwd = std::env::current_dir().with_path(".")?;
+    let root = match &opts.root {
+        Some(root) => cwd.join(root),
+        None => workspace::find_root(&cwd).ok_or_else(|| {
+            Error::Usage(format!(
+                "no Cargo.toml with a [workspace] table in {} or its parents; use '--root'",
+                cwd.display()
+            ))
+        })?,
+    };
+    let template_dir = match opts.template_dir {
+        Some(dir) => Some(cwd.join(dir)),
+        None => {
+            let user_config =
+                credentials::config_dir().map(|dir| dir.join(template_dir::USER_CONFIG));
+            template_dir::configured(&root.join("Cargo.toml"), user_config.as_deref())?
         }
-    }
-
-    Ok(())
-}
-
-struct DayBuilder {
-    opts: Opts,
-    aoc_token: String,
-    aoc_aes_key: Aes256Gcm,
-    base_url: String,
-    package_name: String,
-    display_name: String,
-}
-
-impl DayBuilder {
-    fn new(opts: Opts, aoc_token: String, data_key: String, base_url: String) -> Self {
-        let key = Key::<Aes256Gcm>::from_slice(data_key.as_bytes());
-        let aoc_aes_key = Aes256Gcm::new(key);
-
-        let package_name = format!("day_{:02}", opts.day);
-        let display_name = format!("Day {:02}", opts.day);
-
-        Self {
-            opts,
-            aoc_token,
-            aoc_aes_key,
-            base_url,
-            package_name,
-            display_name,
+    };
+    let opts = Opts {
+        output_dir: opts.output_dir.map(|dir| cwd.join(dir)),
+        template_dir,
+        ..opts
+    };
+    std::env::set_current_dir(&root).with_path(&root)?;
+
+    let data_dir = &layout::data_dir(opts.year);
+    let crates_dir = &layout::year_dir(scaffold::RS.dir, opts.year);
+
+    if let Some(Command::MigrateLayout(migrate)) = &opts.command {
+        let lang_dirs = Lang::value_variants()
+            .iter()
+            .map(|lang| lang.scaffold().dir)
+            .collect::<Vec<_>>();
+        for change in layout::migrate(Path::new("."), migrate.year, &lang_dirs)? {
+            println!("{change}");
         }
+        return Ok(ExitCode::SUCCESS);
     }
 
-    fn write_data_file(&self) -> AnyResult<()> {
-        if self.opts.no_data {
-            return Ok(());
-        }
-
-        let data_dir = Path::new("data");
-        fs::create_dir_all(data_dir)?;
-
-        let data_file = data_dir.join(format!("{}.txt", self.package_name));
-        if data_file.exists() {
-            return Ok(());
+    if let Some(Command::RotateKey) = &opts.command {
+        let old = data_key(&credentials, "AOC_AES_KEY")?;
+        let new = data_key(&credentials, "AOC_NEW_AES_KEY")?;
+        let mut files = Vec::new();
+        for year in layout::years(Path::new(data::DATA_DIR))? {
+            files.extend(data::encrypted_files(&layout::data_dir(year))?);
+            files.extend(readme::encrypted_readmes(&lang_year_dirs(year), None)?);
         }
-
-        let data = self.get_from_aoc(Some("input"))?;
-        fs::write(data_file, &data)?;
-
-        let mut nonce = [0u8; 12];
-        OsRng.fill_bytes(&mut nonce);
-
-        let nonce = Nonce::from(nonce);
-
-        let ciphertext = self.aoc_aes_key.encrypt(&nonce, data.as_bytes()).unwrap();
-
-        let encoded = base64::engine::general_purpose::STANDARD.encode(
-            nonce
-                .iter()
-                .chain(ciphertext.iter())
-                .copied()
-                .collect::<Vec<u8>>(),
+        let rotated = data::rotate_key(&files, &old, &new)?;
+        println!(
+            "Re-encrypted {rotated} inputs and READMEs; 'AOC_AES_KEY' must now be set to the new key"
         );
-
-        fs::write(
-            data_dir.join(format!("{}.enc.txt", self.package_name)),
-            encoded,
-        )
-        .unwrap();
-
-        Ok(())
+        return Ok(ExitCode::SUCCESS);
     }
 
-    fn write_instruction_files(&self) -> AnyResult<()> {
-        let instruction_file = Path::new("instructions.html");
-
-        let instructions_html = if !self.opts.force_download && instruction_file.exists() {
-            let instructions = fs::read_to_string(instruction_file)?;
-            if !self.opts.keep_instructions {
-                fs::remove_file(instruction_file)?;
-            }
-            instructions
-        } else {
-            let instructions = self.get_from_aoc(None)?;
-            if self.opts.keep_instructions {
-                fs::write(instruction_file, &instructions)?;
-            }
-            instructions
+    if opts.decrypt_data {
+        let target = match (&opts.output_dir, opts.stdout) {
+            (_, true) => DecryptTarget::Writer,
+            (Some(dir), _) => DecryptTarget::Dir(dir.clone()),
+            (None, false) => DecryptTarget::Alongside,
         };
-
-        let parts = Regex::new(r"(?s)<article.*?>(.+?)</article>")
-            .unwrap()
-            .captures_iter(&instructions_html)
-            .map(|caps| caps.get(1).unwrap().as_str())
-            .collect::<Vec<&str>>();
-
-        let readme_contents = parts.iter().fold(String::new(), |mut output, part| {
-            let mut iter = part.chars().peekable();
-            while iter.peek().is_some() {
-                let out = recursive_parse_instructions(&self.base_url, &mut iter, false);
-                output.push_str(&out);
-            }
-            output.push('\n');
-            output
-        });
-
-        for lang in &self.opts.langs {
-            let dir = Path::new(&lang.to_string()).join(&self.package_name);
-            fs::create_dir_all(&dir)?;
-            fs::write(dir.join("README.md"), readme_contents.as_str())?;
+        let key = data_key(&credentials, "AOC_AES_KEY")?;
+        data::decrypt_data(
+            data_dir,
+            &key,
+            opts.day.as_ref(),
+            &target,
+            &mut std::io::stdout(),
+        )?;
+        // READMEs only make sense beside their solutions
+        if target == DecryptTarget::Alongside {
+            readme::decrypt_readmes(&lang_year_dirs(opts.year), &key, opts.day.as_ref())?;
         }
+        return Ok(ExitCode::SUCCESS);
+    }
 
-        Ok(())
+    if opts.audit_data {
+        let rows = data::audit(
+            data_dir,
+            crates_dir,
+            &data_key(&credentials, "AOC_AES_KEY")?,
+        )?;
+        print!("{}", data::format_audit(&rows));
+        return Ok(if rows.iter().any(|row| row.status.is_problem()) {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        });
     }
 
-    fn add_go_template(&self) -> AnyResult<()> {
-        if self.opts.skip_templates {
-            return Ok(());
+    if opts.encrypt_data {
+        let key = data_key(&credentials, "AOC_AES_KEY")?;
+        let encrypted = data::encrypt_data(data_dir, &key)?;
+        println!("Encrypted {encrypted} inputs");
+        if opts.encrypt_readmes {
+            let encrypted =
+                readme::encrypt_readmes(&opts.file_writer(), &lang_year_dirs(opts.year), &key)?;
+            println!("Encrypted {encrypted} READMEs");
         }
+        return Ok(ExitCode::SUCCESS);
+    }
 
-        let dir = Path::new("go").join(&self.package_name);
+    if let Some(Command::Login(login)) = &opts.command {
+        let mut token = String::new();
+        std::io::stdin().read_line(&mut token).with_path("stdin")?;
+        let token = token.trim();
+
+        let account = credentials::account_name(&opts.client(token))?;
+        let mut credentials = credentials;
+        let path = credentials.save_session(token, login.expires)?;
+        println!(
+            "Logged in as {account}; saved the session token to {}",
+            path.display()
+        );
+        println!(
+            "{}",
+            credentials::describe_expiry(login.expires, Utc::now().date_naive())
+        );
+        return Ok(ExitCode::SUCCESS);
+    }
 
-        MainGo {
-            package_name: &self.package_name,
+    if let Some(Command::Show(show)) = &opts.command {
+        let day = opts.single_day()?;
+        let path = show::cached_path(opts.year, day);
+        if !path.exists() {
+            return Err(Error::Usage(format!(
+                "no instructions cached for day {day} of {}; fetch them with '--day {day} --download --no-data --skip-templates'",
+                opts.year
+            )));
         }
-        .write(&dir)?;
+        let page = fs::read_to_string(&path).with_path(&path)?;
+        let ansi = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        let Some(text) = show::render(&page, show.part, show.width(), ansi) else {
+            return Err(Error::Usage(format!(
+                "{} has no part {} yet; fetch it with '--day {day} --part-2'",
+                path.display(),
+                show.part.unwrap_or(1)
+            )));
+        };
+        print!("{text}");
+        return Ok(ExitCode::SUCCESS);
+    }
 
-        MainTestGo {}.write(&dir)?;
+    let aoc_token = credentials.get("AOC_SESSION_TOKEN")?;
+    let client = opts.client(&aoc_token);
+
+    if let Some(Command::Check) = &opts.command {
+        println!("Logged in as {}", credentials::account_name(&client)?);
+        println!(
+            "{}",
+            credentials::describe_expiry(
+                credentials.session_expires(&aoc_token)?,
+                Utc::now().date_naive()
+            )
+        );
+        return Ok(ExitCode::SUCCESS);
+    }
 
-        Ok(())
+    if let Some(Command::Submit(submit)) = &opts.command {
+        let verdict = submit::submit(&client, opts.year, opts.single_day()?, submit)?;
+        println!("{verdict}");
+        return Ok(verdict.exit_code());
     }
 
-    fn add_ts_template(&self) -> AnyResult<()> {
-        if self.opts.skip_templates {
-            return Ok(());
+    if let Some(Command::Status(status)) = &opts.command {
+        let calendar = client.get(&client.url(&format!("/{}", opts.year)))?;
+        let rows = status::status(&status::calendar_stars(&calendar), crates_dir, data_dir);
+        if status.json {
+            // Plain structs of numbers and bools always serialise
+            let json = serde_json::to_string_pretty(&rows).expect("status rows serialise");
+            println!("{json}");
+        } else {
+            print!("{}", status::format_status(&rows));
         }
+        return Ok(ExitCode::SUCCESS);
+    }
 
-        let dir = Path::new("ts").join(&self.package_name);
-
-        MainTs {}.write(&dir)?;
-
-        DayTs {
-            package_name: &self.package_name,
+    if let Some(Command::Leaderboard(leaderboard)) = &opts.command {
+        // Checked before fetching, so a bad '--day' costs no request
+        let day = match opts.day {
+            Some(_) => Some(opts.single_day()?),
+            None => None,
+        };
+        let snapshots = leaderboard::load(
+            &client,
+            &SystemClock,
+            Path::new(leaderboard::CACHE_DIR),
+            opts.year,
+            leaderboard.id,
+        )?;
+        print!("{}", leaderboard::format_table(&snapshots.current));
+        if let Some(day) = day {
+            println!();
+            print!(
+                "{}",
+                leaderboard::format_day(&snapshots.current, opts.year, day)?
+            );
         }
-        .write(&dir)?;
-
-        DayTestTs {}.write(&dir)?;
-
-        DayBenchTs {}.write(&dir)?;
+        if let Some(previous) = &snapshots.previous {
+            println!();
+            print!(
+                "{}",
+                leaderboard::format_changes(previous, &snapshots.current)
+            );
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
 
-        Ok(())
+    if opts.keep_instructions && opts.days().single().is_none() {
+        return Err(Error::Usage(
+            "'--keep-instructions' needs a single '--day'".to_string(),
+        ));
     }
 
-    fn add_rs_template(&self) -> AnyResult<()> {
-        if self.opts.skip_templates {
-            return Ok(());
+    let key = data_key(&credentials, "AOC_AES_KEY")?;
+
+    // Report what was done before any failure, so a batch can be picked up again
+    let mut summary = Vec::new();
+    let mut result = Ok(());
+    for day in opts.days().iter() {
+        match fetch_day(&opts, &client, &key, day) {
+            Ok(outcome) => summary.push(format!("Day {day:02}: {outcome}")),
+            Err(err) => {
+                summary.push(format!("Day {day:02}: failed"));
+                result = Err(err);
+                break;
+            }
         }
+    }
+    if !opts.dry_run {
+        println!("{}", summary.join("\n"));
+    }
+    result?;
 
-        let dir = Path::new("rs").join(&self.package_name);
-
-        fs::create_dir_all(dir.join("src").join("bin"))?;
-        fs::create_dir_all(dir.join("benches"))?;
-
-        CargoRs {
-            crate_name: &self.package_name,
-        }
-        .write(&dir)?;
+    Ok(ExitCode::SUCCESS)
+}
 
-        LibRs {
-            crate_name: &self.package_name,
-        }
-        .write(&dir)?;
+/// What happened to one day of a fetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DayOutcome {
+    Fetched,
+    /// The input and part 2 instructions were already on disk.
+    Complete,
+}
 
-        MainRs {
-            crate_name: &self.package_name,
+impl Display for DayOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DayOutcome::Fetched => f.write_str("fetched"),
+            DayOutcome::Complete => f.write_str("skipped, already complete"),
         }
-        .write(&dir)?;
+    }
+}
 
-        BenchRs {
-            crate_name: &self.package_name,
-            display_name: &self.display_name,
-        }
-        .write(&dir)?;
+fn fetch_day(
+    opts: &Opts,
+    client: &AocClient,
+    key: &DataKey,
+    day: u32,
+) -> Result<DayOutcome, Error> {
+    let builder = DayBuilder::new(opts, client, key, day);
+    if !opts.force_download && builder.is_complete() {
+        return Ok(DayOutcome::Complete);
+    }
 
-        Ok(())
+    if opts.wait {
+        let jitter = Duration::from_millis(u64::from(OsRng.next_u32() % 3000));
+        calendar::wait_for_unlock(&SystemClock, opts.year, day, jitter, &mut std::io::stdout())?;
+    } else {
+        calendar::ensure_unlocked(&SystemClock, opts.year, day)?;
     }
 
-    fn get_from_aoc(&self, path: Option<&str>) -> Result<String, Error> {
-        let url = if let Some(path) = path {
-            format!("{}/{}", self.base_url, path)
-        } else {
-            self.base_url.to_owned()
-        };
+    builder.validate_templates()?;
+    builder.write_data_file()?;
+    let puzzle = builder.write_instruction_files()?;
 
-        let c = reqwest::blocking::Client::new();
-        let req = c
-            .get(url)
-            .header("Cookie", format!("session={}", self.aoc_token))
-            .build()?;
+    let vars = builder.vars(&puzzle);
+    for lang in &opts.langs {
+        builder.add_template(*lang, &vars)?;
+    }
 
-        c.execute(req)?.text()
+    if opts.part_2 && opts.langs.contains(&Lang::Rs) {
+        builder.patch_rs_part_2_example(&puzzle.examples)?;
     }
-}
 
-trait Template {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()>;
+    Ok(DayOutcome::Fetched)
 }
 
-#[derive(TemplateOnce)]
-#[template(path = "rs/src/lib.stpl.rs")]
-struct LibRs<'a> {
-    crate_name: &'a str,
+fn data_key(credentials: &Credentials, var: &str) -> Result<DataKey, Error> {
+    Ok(DataKey::new(credentials.get(var)?))
 }
 
-impl<'a> Template for LibRs<'a> {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()> {
-        fs::write(base.as_ref().join("src").join("lib.rs"), {
-            let mut output = self.render_once()?;
-            output.push('\n');
-            output
-        })?;
-        Ok(())
-    }
+/// Each language's folder for `year`, e.g. `rs/y2023`.
+fn lang_year_dirs(year: u32) -> Vec<PathBuf> {
+    Lang::value_variants()
+        .iter()
+        .map(|lang| layout::year_dir(lang.scaffold().dir, year))
+        .collect()
 }
 
-#[derive(TemplateOnce)]
-#[template(path = "rs/Cargo.stpl.toml")]
-struct CargoRs<'a> {
-    crate_name: &'a str,
+/// What the instructions say about the day's puzzle.
+#[derive(Debug, Default)]
+struct Puzzle {
+    title: Option<String>,
+    /// The example from each part.
+    examples: Vec<Example>,
 }
 
-impl<'a> Template for CargoRs<'a> {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()> {
-        fs::write(base.as_ref().join("Cargo.toml"), {
-            let mut output = self.render_once()?;
-            output.push('\n');
-            output
-        })?;
-        Ok(())
-    }
+struct DayBuilder<'a> {
+    opts: &'a Opts,
+    client: &'a AocClient,
+    data_key: &'a DataKey,
+    day: u32,
+    files: FileWriter,
+    template_dir: Option<TemplateDir>,
+    base_url: String,
+    package_name: String,
+    crate_name: String,
+    display_name: String,
 }
 
-#[derive(TemplateOnce)]
-#[template(path = "rs/src/bin/main.stpl.rs")]
-struct MainRs<'a> {
-    crate_name: &'a str,
-}
+impl<'a> DayBuilder<'a> {
+    fn new(opts: &'a Opts, client: &'a AocClient, data_key: &'a DataKey, day: u32) -> Self {
+        let base_url = client.url(&format!("/{}/day/{day}", opts.year));
+        let package_name = format!("day_{day:02}");
+        let crate_name = layout::crate_name(opts.year, day);
+        let display_name = format!("Day {day:02}");
+        let files = opts.file_writer();
+        let template_dir = opts.template_dir.as_ref().map(TemplateDir::new);
 
-impl<'a> Template for MainRs<'a> {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()> {
-        let day = base.as_ref().file_name().unwrap().to_str().unwrap();
-        fs::write(
-            base.as_ref()
-                .join("src")
-                .join("bin")
-                .join(format!("{day}.rs")),
-            {
-                let mut output = self.render_once()?;
-                output.push('\n');
-                output
-            },
-        )?;
-        Ok(())
+        Self {
+            opts,
+            client,
+            data_key,
+            day,
+            files,
+            template_dir,
+            base_url,
+            package_name,
+            crate_name,
+            display_name,
+        }
     }
-}
-
-#[derive(TemplateOnce)]
-#[template(path = "rs/benches/bench.stpl.rs")]
-struct BenchRs<'a> {
-    crate_name: &'a str,
-    display_name: &'a str,
-}
 
-impl<'a> Template for BenchRs<'a> {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()> {
-        fs::write(base.as_ref().join("benches").join("bench.rs"), {
-            let mut output = self.render_once()?;
-            output.push('\n');
-            output
-        })?;
-        Ok(())
+    /// Whether the input and the part 2 instructions for every language are
+    /// already on disk, so there's nothing left to fetch.
+    fn is_complete(&self) -> bool {
+        (self.opts.no_data || self.data_file().exists())
+            && self.opts.langs.iter().all(|lang| {
+                fs::read_to_string(self.readme_path(*lang))
+                    .is_ok_and(|readme| readme.contains("--- Part Two ---"))
+            })
     }
-}
 
-#[derive(TemplateOnce)]
-#[template(path = "go/main.stpl.go")]
-struct MainGo<'a> {
-    package_name: &'a str,
-}
-
-impl<'a> Template for MainGo<'a> {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()> {
-        fs::write(base.as_ref().join("main.go"), {
-            let mut output = self.render_once()?;
-            output.push('\n');
-            output
-        })?;
-        Ok(())
+    fn data_file(&self) -> PathBuf {
+        layout::data_dir(self.opts.year).join(format!("{}.txt", self.package_name))
     }
-}
 
-#[derive(TemplateOnce)]
-#[template(path = "go/main_test.stpl.go")]
-struct MainTestGo {}
-
-impl Template for MainTestGo {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()> {
-        fs::write(base.as_ref().join("main_test.go"), {
-            let mut output = self.render_once()?;
-            output.push('\n');
-            output
-        })?;
-        Ok(())
-    }
-}
+    fn write_data_file(&self) -> Result<(), Error> {
+        if self.opts.no_data {
+            return Ok(());
+        }
 
-#[derive(TemplateOnce)]
-#[template(path = "ts/main.stpl.ts")]
-struct MainTs {}
-
-impl Template for MainTs {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()> {
-        fs::write(base.as_ref().join("main.ts"), {
-            let mut output = self.render_once()?;
-            output.push('\n');
-            output
-        })?;
-        Ok(())
-    }
-}
+        let data_file = self.data_file();
+        if data_file.exists() {
+            return Ok(());
+        }
 
-#[derive(TemplateOnce)]
-#[template(path = "ts/day.stpl.ts")]
-struct DayTs<'a> {
-    package_name: &'a str,
-}
+        if self.files.dry_run {
+            for file in [&data_file, &data::encrypted_path(&data_file)] {
+                println!("Would create {}", file.display());
+            }
+            return Ok(());
+        }
 
-impl<'a> Template for DayTs<'a> {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()> {
-        fs::write(base.as_ref().join("day.ts"), {
-            let mut output = self.render_once()?;
-            output.push('\n');
-            output
-        })?;
-        Ok(())
-    }
-}
+        let data = self.get_from_aoc(Some("input"))?;
+        let encrypted_file = data::encrypted_path(&data_file);
+        let encrypted = self
+            .data_key
+            .encrypt(data.as_bytes())
+            .map_err(|err| Error::Crypto {
+                path: encrypted_file.clone(),
+                err,
+            })?;
+
+        let data_dir = data_file.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(data_dir).with_path(data_dir)?;
+        fs::write(&data_file, &data).with_path(&data_file)?;
+        fs::write(&encrypted_file, encrypted).with_path(&encrypted_file)?;
 
-#[derive(TemplateOnce)]
-#[template(path = "ts/day.bench.stpl.ts")]
-struct DayBenchTs {}
-
-impl Template for DayBenchTs {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()> {
-        fs::write(base.as_ref().join("day.bench.ts"), {
-            let mut output = self.render_once()?;
-            output.push('\n');
-            output
-        })?;
         Ok(())
     }
-}
 
-#[derive(TemplateOnce)]
-#[template(path = "ts/day.test.stpl.ts")]
-struct DayTestTs {}
-
-impl Template for DayTestTs {
-    fn write(self, base: impl AsRef<Path>) -> AnyResult<()> {
-        fs::write(base.as_ref().join("day.test.ts"), {
-            let mut output = self.render_once()?;
-            output.push('\n');
-            output
-        })?;
+    /// Checks a custom template directory has everything the chosen languages
+    /// need, before anything is written.
+    fn validate_templates(&self) -> Result<(), Error> {
+        let Some(template_dir) = &self.template_dir else {
+            return Ok(());
+        };
+        if self.opts.skip_templates {
+            return Ok(());
+        }
+
+        let sources = self
+            .opts
+            .langs
+            .iter()
+            .flat_map(|lang| lang.scaffold().files)
+            .map(|file| file.source)
+            .collect::<Vec<_>>();
+        template_dir.validate(&sources, &Vars::NAMES)?;
         Ok(())
     }
-}
 
-fn recursive_parse_instructions<'a>(
-    url: &'a str,
-    input: &mut Peekable<Chars<'a>>,
-    is_preformatted: bool,
-) -> String {
-    let whole_tag = input.take_while(|c| c.ne(&'>')).collect::<String>();
-
-    let raw_tag = whole_tag
-        .chars()
-        .take_while(|&c| c.ne(&' '))
-        .collect::<String>();
-
-    let mut tag = raw_tag.trim();
-    if let Some(t) = tag.strip_prefix('<') {
-        tag = t;
-    }
-
-    let mut pre = is_preformatted;
-    let mut output = String::new();
-
-    match tag {
-        "h2" => output.push_str("\n## "),
-        "em" if is_preformatted => output.push_str("<b>"),
-        "code" if is_preformatted => output.push_str("<code>"),
-        "em" => output.push_str("**"),
-        "code" => output.push('`'),
-        "pre" => {
-            output.push_str("\n\n<pre>");
-            pre = true
+    /// Writes the README for each language, returning what the instructions
+    /// say about the puzzle.
+    fn write_instruction_files(&self) -> Result<Puzzle, Error> {
+        let readmes = self
+            .opts
+            .langs
+            .iter()
+            .map(|lang| self.readme_path(*lang))
+            .collect::<Vec<_>>();
+        if let Some(warning) = readme::unignored_warning(&readmes) {
+            eprintln!("{warning}");
         }
-        "p" => output.push_str("\n\n"),
-        "ul" => output.push('\n'),
-        "li" => output.push_str("  - "),
-        "a" => output.push('['),
-        "span" => {}
-        "" if input.peek().is_none() => {}
-        _ => {
-            panic!("unknown tag: {}", tag)
-        }
-    }
 
-    loop {
-        match match input.next() {
-            Some(v) => v,
-            None => return output,
-        } {
-            '<' => {
-                if let Some('/') = input.peek() {
-                    let _ = input.take_while(|c| c.ne(&'>')).collect::<String>();
-                    break;
+        if self.files.dry_run {
+            for readme in &readmes {
+                let encrypted = readme::encrypted_path(readme);
+                let encrypted = self.opts.encrypt_readmes.then_some(&encrypted);
+                for file in std::iter::once(readme).chain(encrypted) {
+                    let action = if file.exists() { "modify" } else { "create" };
+                    println!("Would {action} {}", file.display());
                 }
-                output.push_str(&recursive_parse_instructions(url, input, pre));
-            }
-            '>' => break,
-            v => {
-                output.push(v);
             }
+            return Ok(Puzzle::default());
         }
-    }
 
-    match tag {
-        "h2" if output.contains(" --- Day") => {
-            output = output.replace("\n## ", "# [");
-            output.push_str(format!("]({})", url).as_str());
-        }
-        "em" if is_preformatted => output.push_str("</b>"),
-        "code" if is_preformatted => output.push_str("</code>"),
-        "em" => output.push_str("**"),
-        "code" => output.push('`'),
-        "pre" => output.push_str("</pre>"),
-        "a" => {
-            let link = Regex::new(r#"href="(.+?)""#)
-                .unwrap()
-                .captures(&whole_tag)
-                .map(|caps| caps.get(1).unwrap().as_str())
-                .unwrap();
-            output.push_str(&format!("]({})", link));
+        let instruction_file = Path::new("instructions.html");
+
+        // Only a single day's instructions can have been kept
+        let cached = self.opts.days().single().is_some() && instruction_file.exists();
+        let instructions_html = if !self.opts.force_download && cached {
+            let instructions = fs::read_to_string(instruction_file).with_path(instruction_file)?;
+            if !self.opts.keep_instructions {
+                fs::remove_file(instruction_file).with_path(instruction_file)?;
+            }
+            instructions
+        } else {
+            let instructions = self.get_from_aoc(None)?;
+            if self.opts.keep_instructions {
+                fs::write(instruction_file, &instructions).with_path(instruction_file)?;
+            }
+            instructions
+        };
+        let cache_file = show::cached_path(self.opts.year, self.day);
+        let cache_dir = cache_file.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(cache_dir).with_path(cache_dir)?;
+        fs::write(&cache_file, &instructions_html).with_path(&cache_file)?;
+
+        let tree = html::parse(&instructions_html);
+        let articles = html::find_all(&tree, "article");
+        if articles.is_empty() {
+            return Err(Error::Html {
+                url: self.base_url.clone(),
+                reason: "no <article> holding the instructions".to_string(),
+            });
         }
-        "p" | "span" | "h2" | "ul" | "li" => {}
-        _ => {
-            panic!("unknown tag: {}", tag)
+        let title = html::find_all(&tree, "h2")
+            .first()
+            .and_then(|heading| markdown::day_title(&heading.text()).map(str::to_string));
+        let front_matter = markdown::FrontMatter {
+            title: title.as_deref(),
+            year: self.opts.year,
+            day: self.day,
+            url: &self.base_url,
+            fetched: Utc::now(),
+            parts: articles.len(),
+        };
+
+        let mut footnotes = Vec::new();
+        let readme_contents =
+            articles
+                .iter()
+                .fold(front_matter.to_string(), |mut output, article| {
+                    output.push_str(&markdown::render(
+                        &article.children,
+                        &self.base_url,
+                        &mut footnotes,
+                    ));
+                    output.push('\n');
+                    output
+                });
+
+        for readme in &readmes {
+            self.files.update(readme, readme_contents.as_str())?;
+            if self.opts.encrypt_readmes {
+                readme::store(&self.files, readme, &readme_contents, self.data_key)?;
+            }
         }
-    }
 
-    output = output.trim_end().to_string();
+        Ok(Puzzle {
+            title,
+            examples: articles.into_iter().map(Example::extract).collect(),
+        })
+    }
 
-    // hack to ensure emphasised code blocks have the correct operation order
-    if output.starts_with('`') && output.contains("**") {
-        output = format!("**{}**", &output.replace("**", ""));
+    /// The day's folder for `lang`, e.g. `rs/y2023/day_05`.
+    fn day_dir(&self, lang: Lang) -> PathBuf {
+        layout::year_dir(lang.scaffold().dir, self.opts.year).join(&self.package_name)
     }
 
-    if !is_preformatted {
-        output = output.replace("&gt;", ">");
-        output = output.replace("&lt;", "<");
+    fn readme_path(&self, lang: Lang) -> PathBuf {
+        self.day_dir(lang).join(lang.scaffold().readme)
     }
 
-    output
-}
+    fn add_template(&self, lang: Lang, vars: &Vars) -> Result<(), Error> {
+        if self.opts.skip_templates {
+            return Ok(());
+        }
 
-fn decrypt_data(key: &[u8]) -> AnyResult<()> {
-    let key = Key::<Aes256Gcm>::from_slice(key);
-    let cipher = Aes256Gcm::new(key);
+        let dir = self.day_dir(lang);
 
-    let dir = fs::read_dir("./data")?;
-    for file in dir {
-        let file = file?;
-        if !file.file_name().to_str().unwrap().ends_with(".enc.txt") {
-            continue;
+        let mut rendered = Vec::new();
+        for file in lang.scaffold().files {
+            let path = file.path(vars);
+            let contents = file.render(vars, self.template_dir.as_ref())?;
+            self.files.write(&dir.join(&path), &contents)?;
+            rendered.push((path, contents));
         }
 
-        let contents = fs::read_to_string(file.path()).unwrap();
+        if lang == Lang::Rs {
+            // Whatever is on disk wins, as a conflicting template isn't written
+            let manifest_path = dir.join("Cargo.toml");
+            let member_manifest = fs::read_to_string(&manifest_path)
+                .or_else(|err| {
+                    rendered
+                        .iter()
+                        .find(|(path, _)| path == "Cargo.toml")
+                        .map(|(_, contents)| contents.clone())
+                        .ok_or(err)
+                })
+                .with_path(&manifest_path)?;
+            workspace::register_member(
+                Path::new("Cargo.toml"),
+                &dir.to_string_lossy(),
+                &member_manifest,
+                &self.files,
+            )?;
+        }
 
-        let decoded = base64::engine::general_purpose::STANDARD
-            .decode(contents)
-            .unwrap();
+        Ok(())
+    }
 
-        let (nonce, ciphertext) = decoded.split_at(12);
-        let nonce = Nonce::from_iter(nonce.iter().copied());
+    /// Fills in the part 2 example expectation of an existing `lib.rs`,
+    /// leaving the rest of the solution alone.
+    fn patch_rs_part_2_example(&self, examples: &[Example]) -> Result<(), Error> {
+        let Some(part_2) = examples.get(1) else {
+            return Ok(());
+        };
+        let lib_rs = self.day_dir(Lang::Rs).join("src").join("lib.rs");
+        let expected = part_2.expected_literal();
+        if expected == "todo!()" || !lib_rs.exists() {
+            return Ok(());
+        }
 
-        let decrypted = cipher.decrypt(&nonce, ciphertext).unwrap();
+        match example::patch_part_2(&fs::read_to_string(&lib_rs).with_path(&lib_rs)?, &expected) {
+            Some(patched) => {
+                self.files.update(&lib_rs, &patched)?;
+            }
+            None => println!(
+                "Left the part 2 example in {} as is; expected {expected}",
+                lib_rs.display()
+            ),
+        }
+        Ok(())
+    }
 
-        fs::write(
-            file.path().parent().unwrap().join(
-                file.file_name()
-                    .to_str()
-                    .unwrap()
-                    .replace(".enc.txt", ".txt"),
+    fn vars(&self, puzzle: &Puzzle) -> Vars {
+        let part_1 = puzzle.examples.first().cloned().unwrap_or_default();
+        let (display_name, description) = match &puzzle.title {
+            Some(title) => (
+                format!("{}: {title}", self.display_name),
+                format!(
+                    "Advent of Code {}, day {}: {title}",
+                    self.opts.year, self.day
+                ),
             ),
-            decrypted,
-        )?;
+            None => (
+                self.display_name.clone(),
+                format!("Advent of Code {}, day {}", self.opts.year, self.day),
+            ),
+        };
+        Vars {
+            crate_name: self.crate_name.clone(),
+            package_name: self.package_name.clone(),
+            // Debug quotes and escapes a string as Rust expects
+            display_name_literal: format!("{display_name:?}"),
+            display_name,
+            description: toml_edit::Value::from(description).to_string(),
+            day: self.day,
+            year: self.opts.year,
+            title: puzzle
+                .title
+                .clone()
+                .unwrap_or_else(|| self.display_name.clone()),
+            example_input: part_1.input_literal(),
+            part_1_expected: part_1.expected_literal(),
+        }
     }
 
-    Ok(())
+    fn get_from_aoc(&self, path: Option<&str>) -> Result<String, FetchError> {
+        let url = if let Some(path) = path {
+            format!("{}/{}", self.base_url, path)
+        } else {
+            self.base_url.to_owned()
+        };
+
+        self.client.get(&url)
+    }
 }
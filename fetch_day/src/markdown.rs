@@ -0,0 +1,367 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::html::{Element, Node};
+
+/// Renders the contents of an instruction `<article>` as Markdown.
+///
+/// `url` is the puzzle page, which the `--- Day N: Title ---` heading links to.
+///
+/// Hover text from `<span title="...">`, where AoC hides its jokes and easter
+/// eggs, becomes footnotes at the end of the article. They're added to
+/// `footnotes`, which numbers them across every article of the page.
+pub fn render(nodes: &[Node], url: &str, footnotes: &mut Vec<String>) -> String {
+    let first = footnotes.len();
+    let mut renderer = Renderer {
+        url,
+        pre: false,
+        lists: Vec::new(),
+        item_indent: None,
+        footnotes,
+    };
+
+    let mut output = nodes
+        .iter()
+        .filter(|node| !node.is_whitespace())
+        .map(|node| renderer.node(node))
+        .collect::<String>();
+    for (i, footnote) in footnotes.iter().enumerate().skip(first) {
+        output.push_str(&format!("\n\n[^{}]: {footnote}", i + 1));
+    }
+    output
+}
+
+/// The text of each footnote defined in rendered Markdown.
+pub fn footnotes(markdown: &str) -> Vec<&str> {
+    markdown
+        .lines()
+        .filter_map(|line| line.strip_prefix("[^")?.split_once("]: "))
+        .map(|(_, text)| text)
+        .collect()
+}
+
+/// The title from a `--- Day N: Title ---` heading.
+pub fn day_title(heading: &str) -> Option<&str> {
+    let (_, title) = heading.trim().strip_prefix("--- Day ")?.split_once(':')?;
+    Some(title.strip_suffix("---")?.trim())
+}
+
+/// YAML front matter recording where a README's instructions came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrontMatter<'a> {
+    pub title: Option<&'a str>,
+    pub year: u32,
+    pub day: u32,
+    pub url: &'a str,
+    pub fetched: DateTime<Utc>,
+    /// How many parts' instructions the page held.
+    pub parts: usize,
+}
+
+impl Display for FrontMatter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "---")?;
+        if let Some(title) = self.title {
+            // A JSON string is a valid YAML one, whatever the title holds
+            writeln!(f, "title: {}", serde_json::Value::from(title))?;
+        }
+        writeln!(f, "year: {}", self.year)?;
+        writeln!(f, "day: {}", self.day)?;
+        writeln!(f, "url: {}", self.url)?;
+        writeln!(
+            f,
+            "fetched: {}",
+            self.fetched.to_rfc3339_opts(SecondsFormat::Secs, true)
+        )?;
+        let parts = (1..=self.parts)
+            .map(|part| part.to_string())
+            .collect::<Vec<_>>();
+        writeln!(f, "parts: [{}]", parts.join(", "))?;
+        writeln!(f, "---\n")
+    }
+}
+
+struct Renderer<'a> {
+    url: &'a str,
+    /// Inside a `<pre>` block, which is emitted as HTML rather than Markdown.
+    pre: bool,
+    /// Open lists, innermost last.
+    lists: Vec<List>,
+    /// Column at which the contents of the current list item start.
+    item_indent: Option<String>,
+    footnotes: &'a mut Vec<String>,
+}
+
+struct List {
+    /// Last item number, for ordered lists.
+    number: Option<usize>,
+    indent: String,
+}
+
+impl<'a> Renderer<'a> {
+    fn node(&mut self, node: &Node) -> String {
+        match node {
+            Node::Text(text) if self.pre => htmlize::escape_text(text.as_str()).into_owned(),
+            Node::Text(text) => text.to_owned(),
+            Node::Element(el) => {
+                let output = self.element(el);
+                if self.pre || el.tag == "br" {
+                    output
+                } else {
+                    output.trim_end().to_string()
+                }
+            }
+        }
+    }
+
+    fn children(&mut self, el: &Element) -> String {
+        el.children.iter().map(|child| self.node(child)).collect()
+    }
+
+    fn element(&mut self, el: &Element) -> String {
+        match el.tag.as_str() {
+            "h2" => {
+                let heading = self.children(el);
+                if heading.trim_start().starts_with("--- Day") {
+                    format!("# [{}]({})", heading.trim(), self.url)
+                } else {
+                    format!("\n## {heading}")
+                }
+            }
+            "p" => format!("\n\n{}", self.children(el)),
+            "pre" => {
+                let was_pre = std::mem::replace(&mut self.pre, true);
+                let contents = self.children(el);
+                self.pre = was_pre;
+                format!("\n\n<pre>{contents}</pre>")
+            }
+            "em" if self.pre => format!("<b>{}</b>", self.children(el)),
+            "em" => format!("**{}**", self.children(el)),
+            "code" if self.pre => format!("<code>{}</code>", self.children(el)),
+            // Markdown can't style inside a code span, so emphasis moves outside it
+            "code" if el.contains_tag("em") => format!("**`{}`**", el.text()),
+            "code" => format!("`{}`", el.text()),
+            "s" if self.pre => format!("<s>{}</s>", self.children(el)),
+            "s" => format!("~~{}~~", self.children(el)),
+            "sup" => format!("<sup>{}</sup>", self.children(el)),
+            "br" if self.pre => "\n".to_string(),
+            "br" => "  \n".to_string(),
+            "ul" | "ol" => {
+                self.lists.push(List {
+                    number: (el.tag == "ol").then_some(0),
+                    indent: self.item_indent.clone().unwrap_or_else(|| "  ".to_string()),
+                });
+                let items = el
+                    .children
+                    .iter()
+                    .filter(|child| !child.is_whitespace())
+                    .map(|child| self.node(child))
+                    .collect::<String>();
+                self.lists.pop();
+
+                if self.lists.is_empty() {
+                    format!("\n{items}")
+                } else {
+                    items
+                }
+            }
+            "li" => {
+                let (indent, marker) = match self.lists.last_mut() {
+                    Some(List {
+                        number: Some(n),
+                        indent,
+                    }) => {
+                        *n += 1;
+                        (indent.clone(), format!("{n}."))
+                    }
+                    Some(List { indent, .. }) => (indent.clone(), "-".to_string()),
+                    None => (String::new(), "-".to_string()),
+                };
+                let outer_indent = self
+                    .item_indent
+                    .replace(format!("{indent}{}", " ".repeat(marker.len() + 1)));
+
+                let mut contents = String::new();
+                for child in &el.children {
+                    let output = self.node(child);
+                    if matches!(child, Node::Element(nested) if nested.tag == "ul" || nested.tag == "ol")
+                    {
+                        contents.truncate(contents.trim_end().len());
+                    }
+                    contents.push_str(&output);
+                }
+                self.item_indent = outer_indent;
+
+                format!("\n{indent}{marker} {}", contents.trim())
+            }
+            "a" => {
+                let text = self.children(el);
+                match el.attr("href") {
+                    Some(href) => format!("[{text}]({href})"),
+                    None => text,
+                }
+            }
+            "span" => {
+                let text = self.children(el);
+                match el.attr("title") {
+                    Some(title) if self.pre => {
+                        format!(
+                            "<span title=\"{}\">{text}</span>",
+                            htmlize::escape_attribute(title)
+                        )
+                    }
+                    Some(title) if !title.trim().is_empty() => {
+                        self.footnotes
+                            .push(title.split_whitespace().collect::<Vec<_>>().join(" "));
+                        format!("{text}[^{}]", self.footnotes.len())
+                    }
+                    _ => text,
+                }
+            }
+            "script" | "style" => String::new(),
+            _ => self.children(el),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html;
+
+    #[test]
+    fn day_title() {
+        assert_eq!(
+            Some("Fixture Fountain"),
+            super::day_title(" --- Day 0: Fixture Fountain --- ")
+        );
+        assert_eq!(None, super::day_title("--- Part Two ---"));
+    }
+
+    const URL: &str = "https://adventofcode.com/2023/day/0";
+
+    mod render {
+        use super::*;
+
+        struct Case {
+            input: &'static str,
+            url: &'static str,
+            expected: &'static str,
+        }
+
+        #[test]
+        fn puzzle_page() {
+            run(&Case {
+                input: include_str!("../fixtures/instructions/puzzle.html"),
+                url: URL,
+                expected: include_str!("../fixtures/instructions/puzzle.md"),
+            })
+        }
+
+        #[test]
+        fn extended_markup() {
+            run(&Case {
+                input: include_str!("../fixtures/instructions/extended.html"),
+                url: URL,
+                expected: include_str!("../fixtures/instructions/extended.md"),
+            })
+        }
+
+        #[test]
+        fn saved_2023_day_01() {
+            run(&Case {
+                input: include_str!("../fixtures/instructions/2023/day_01.html"),
+                url: "https://adventofcode.com/2023/day/1",
+                expected: include_str!("../fixtures/instructions/2023/day_01.md"),
+            })
+        }
+
+        #[test]
+        fn saved_2023_day_07() {
+            run(&Case {
+                input: include_str!("../fixtures/instructions/2023/day_07.html"),
+                url: "https://adventofcode.com/2023/day/7",
+                expected: include_str!("../fixtures/instructions/2023/day_07.md"),
+            })
+        }
+
+        fn run(test: &Case) {
+            let tree = html::parse(test.input);
+            let mut footnotes = Vec::new();
+            let actual = html::find_all(&tree, "article").iter().fold(
+                String::new(),
+                |mut output, article| {
+                    output.push_str(&render(&article.children, test.url, &mut footnotes));
+                    output.push('\n');
+                    output
+                },
+            );
+            assert_eq!(test.expected, actual)
+        }
+    }
+
+    #[test]
+    fn front_matter() {
+        let front_matter = FrontMatter {
+            title: Some("Gear \"Ratios\": Redux"),
+            year: 2023,
+            day: 3,
+            url: URL,
+            fetched: DateTime::parse_from_rfc3339("2023-12-03T05:00:07.123Z")
+                .unwrap()
+                .into(),
+            parts: 2,
+        };
+
+        assert_eq!(
+            "\
+---
+title: \"Gear \\\"Ratios\\\": Redux\"
+year: 2023
+day: 3
+url: https://adventofcode.com/2023/day/0
+fetched: 2023-12-03T05:00:07Z
+parts: [1, 2]
+---
+
+",
+            front_matter.to_string()
+        );
+        assert!(!FrontMatter {
+            title: None,
+            parts: 1,
+            ..front_matter
+        }
+        .to_string()
+        .contains("title"));
+    }
+
+    #[test]
+    fn footnotes_number_across_parts() {
+        let tree = html::parse(
+            "<article><p><span title=\"One.\">a</span> <span title=\"\">b</span></p></article>\
+             <article><p><span title=\"Two,\n  wrapped.\">c</span></p>\
+             <pre><code><span title=\"&quot;Three&quot;\">d</span></code></pre></article>",
+        );
+        let mut footnotes = Vec::new();
+
+        let parts = html::find_all(&tree, "article")
+            .iter()
+            .map(|article| render(&article.children, URL, &mut footnotes))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                "\n\na[^1] b\n\n[^1]: One.",
+                "\n\nc[^2]\n\n<pre><code><span title=\"&quot;Three&quot;\">d</span></code></pre>\n\n[^2]: Two, wrapped.",
+            ],
+            parts
+        );
+        assert_eq!(vec!["One.", "Two, wrapped."], footnotes);
+        assert_eq!(
+            vec!["One.", "Two, wrapped."],
+            super::footnotes(&parts.concat())
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    crypto::DataKey,
+    data,
+    days::DaySet,
+    error::{Error, WithPath},
+    files::{FileWriter, Outcome},
+};
+
+/// Encrypted copy of a day's README, which can be committed in its place.
+pub const ENCRYPTED_README: &str = "README.enc.md";
+
+/// `rs/y2023/day_05/README.md` -> `rs/y2023/day_05/README.enc.md`
+pub fn encrypted_path(readme: &Path) -> PathBuf {
+    readme.with_file_name(ENCRYPTED_README)
+}
+
+/// Stores `contents` encrypted next to `readme` through `files`, which like
+/// the README itself is regenerated, so only its `dry_run` applies.
+///
+/// Every encryption differs, so an existing copy which already decrypts to
+/// `contents` is kept rather than churned.
+pub fn store(
+    files: &FileWriter,
+    readme: &Path,
+    contents: &str,
+    key: &DataKey,
+) -> Result<Outcome, Error> {
+    let encrypted = encrypted_path(readme);
+    let unchanged = fs::read_to_string(&encrypted)
+        .ok()
+        .and_then(|existing| key.decrypt(&existing).ok())
+        .is_some_and(|existing| existing == contents.as_bytes());
+    if unchanged {
+        return Ok(Outcome::Unchanged);
+    }
+
+    let sealed = data::sealed(key, contents.as_bytes()).map_err(|err| Error::Crypto {
+        path: encrypted.clone(),
+        err,
+    })?;
+    files.update(&encrypted, &sealed)
+}
+
+/// Encrypted READMEs in the day folders of `year_dirs`, e.g. `rs/y2023`, for
+/// `days`, or every day if `None`.
+pub fn encrypted_readmes(
+    year_dirs: &[PathBuf],
+    days: Option<&DaySet>,
+) -> Result<Vec<PathBuf>, Error> {
+    let mut readmes = Vec::new();
+    for dir in year_dirs.iter().filter(|dir| dir.is_dir()) {
+        for entry in fs::read_dir(dir).with_path(dir)? {
+            let day_dir = entry.with_path(dir)?.path();
+            let encrypted = day_dir.join(ENCRYPTED_README);
+            if encrypted.exists()
+                && days
+                    .is_none_or(|days| data::day_of(&day_dir).is_some_and(|day| days.contains(day)))
+            {
+                readmes.push(encrypted);
+            }
+        }
+    }
+    readmes.sort();
+    Ok(readmes)
+}
+
+/// Restores the plaintext README next to each encrypted one, returning how
+/// many were written. READMEs which already match are left untouched.
+pub fn decrypt_readmes(
+    year_dirs: &[PathBuf],
+    key: &DataKey,
+    days: Option<&DaySet>,
+) -> Result<usize, Error> {
+    let mut restored = 0;
+    for encrypted in encrypted_readmes(year_dirs, days)? {
+        let decrypted = key
+            .decrypt(&fs::read_to_string(&encrypted).with_path(&encrypted)?)
+            .map_err(|err| Error::Crypto {
+                path: encrypted.clone(),
+                err,
+            })?;
+
+        let readme = encrypted.with_file_name("README.md");
+        if fs::read(&readme).is_ok_and(|existing| existing == decrypted) {
+            continue;
+        }
+        fs::write(&readme, decrypted).with_path(&readme)?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+/// (Re)creates the encrypted copy of every README in the day folders of
+/// `year_dirs` through `files`, returning how many were written.
+pub fn encrypt_readmes(
+    files: &FileWriter,
+    year_dirs: &[PathBuf],
+    key: &DataKey,
+) -> Result<usize, Error> {
+    let mut encrypted = 0;
+    for dir in year_dirs.iter().filter(|dir| dir.is_dir()) {
+        let mut readmes = Vec::new();
+        for entry in fs::read_dir(dir).with_path(dir)? {
+            let readme = entry.with_path(dir)?.path().join("README.md");
+            if readme.exists() {
+                readmes.push(readme);
+            }
+        }
+        readmes.sort();
+
+        for readme in readmes {
+            let contents = fs::read_to_string(&readme).with_path(&readme)?;
+            if matches!(
+                store(files, &readme, &contents, key)?,
+                Outcome::Created | Outcome::Overwritten
+            ) {
+                encrypted += 1;
+            }
+        }
+    }
+    Ok(encrypted)
+}
+
+/// Whether git would leave `path` out of a commit, or `None` outside a git
+/// repository or without git installed. Tracked files are never ignored.
+pub fn is_ignored(path: &Path) -> Option<bool> {
+    // Run from beside the path, so it's checked against its own repository
+    let path = std::path::absolute(path).ok()?;
+    let dir = path.ancestors().skip(1).find(|dir| dir.is_dir())?;
+    let status = Command::new("git")
+        .current_dir(dir)
+        .args(["check-ignore", "--quiet", "--"])
+        .arg(&path)
+        .status()
+        .ok()?;
+    match status.code() {
+        Some(0) => Some(true),
+        Some(1) => Some(false),
+        _ => None,
+    }
+}
+
+/// Warning for READMEs holding puzzle text which git would commit, if any.
+pub fn unignored_warning(readmes: &[PathBuf]) -> Option<String> {
+    let unignored = readmes
+        .iter()
+        .filter(|readme| is_ignored(readme) == Some(false))
+        .map(|readme| format!("  {}", readme.display()))
+        .collect::<Vec<_>>();
+    if unignored.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "Warning: these hold puzzle text, which AoC asks isn't redistributed, but aren't ignored by git:\n{}\n\
+         Add 'README.md' to .gitignore and commit the '{ENCRYPTED_README}' written by '--encrypt-readmes' instead",
+        unignored.join("\n")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn round_trip() {
+        let root = TempDir::new("readme_round_trip");
+        let year_dir = root.join("rs").join("y2023");
+        let key = DataKey::new("key");
+        for day in ["day_01", "day_02"] {
+            fs::create_dir_all(year_dir.join(day)).unwrap();
+            fs::write(year_dir.join(day).join("README.md"), format!("# {day}\n")).unwrap();
+        }
+        let year_dirs = std::slice::from_ref(&year_dir);
+
+        assert_eq!(
+            2,
+            encrypt_readmes(&FileWriter::default(), year_dirs, &key).unwrap()
+        );
+        let encrypted = fs::read_to_string(year_dir.join("day_01").join(ENCRYPTED_README)).unwrap();
+        assert!(!encrypted.contains("day_01"));
+        // Unchanged READMEs keep their existing encryption
+        assert_eq!(
+            0,
+            encrypt_readmes(&FileWriter::default(), year_dirs, &key).unwrap()
+        );
+
+        for day in ["day_01", "day_02"] {
+            fs::remove_file(year_dir.join(day).join("README.md")).unwrap();
+        }
+        let days = DaySet::from(2);
+        assert_eq!(1, decrypt_readmes(year_dirs, &key, Some(&days)).unwrap());
+        assert!(!year_dir.join("day_01").join("README.md").exists());
+        assert_eq!(
+            "# day_02\n",
+            fs::read_to_string(year_dir.join("day_02").join("README.md")).unwrap()
+        );
+
+        assert_eq!(1, decrypt_readmes(year_dirs, &key, None).unwrap());
+        assert_eq!(0, decrypt_readmes(year_dirs, &key, None).unwrap());
+    }
+
+    #[test]
+    fn store_dry_run() {
+        let root = TempDir::new("readme_store_dry_run");
+        let readme = root.join("README.md");
+        let files = FileWriter {
+            dry_run: true,
+            ..FileWriter::default()
+        };
+
+        let outcome = store(&files, &readme, "# day_01\n", &DataKey::new("key")).unwrap();
+
+        assert_eq!(Outcome::WouldCreate, outcome);
+        assert!(!encrypted_path(&readme).exists());
+    }
+
+    #[test]
+    fn rotated_with_inputs() {
+        let root = TempDir::new("readme_rotated_with_inputs");
+        let (data_dir, year_dir) = (root.join("data"), root.join("rs").join("y2023"));
+        let (old, new) = (DataKey::new("old"), DataKey::new("new"));
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(
+            data_dir.join("day_01.enc.txt"),
+            old.encrypt(b"one").unwrap(),
+        )
+        .unwrap();
+        fs::create_dir_all(year_dir.join("day_01")).unwrap();
+        let readme = year_dir.join("day_01").join("README.md");
+        store(&FileWriter::default(), &readme, "# day_01\n", &old).unwrap();
+
+        let mut files = data::encrypted_files(&data_dir).unwrap();
+        files.extend(encrypted_readmes(std::slice::from_ref(&year_dir), None).unwrap());
+        assert_eq!(2, data::rotate_key(&files, &old, &new).unwrap());
+
+        let encrypted = fs::read_to_string(encrypted_path(&readme)).unwrap();
+        assert_eq!(b"# day_01\n".to_vec(), new.decrypt(&encrypted).unwrap());
+    }
+
+    #[test]
+    fn warns_about_unignored_readmes() {
+        let root = TempDir::new("readme_unignored");
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .current_dir(&root)
+                .args(args)
+                .output()
+                .is_ok_and(|output| output.status.success())
+        };
+        if !git(&["init", "--quiet"]) {
+            // Without git there's nothing to warn about
+            assert_eq!(None, unignored_warning(&[root.join("README.md")]));
+            return;
+        }
+        fs::write(root.join(".gitignore"), "/go/**/README.md\n").unwrap();
+        let ignored = root
+            .join("go")
+            .join("y2023")
+            .join("day_05")
+            .join("README.md");
+        let unignored = root
+            .join("rs")
+            .join("y2023")
+            .join("day_05")
+            .join("README.md");
+
+        let warning = unignored_warning(&[ignored.clone(), unignored.clone()]).unwrap();
+
+        assert!(
+            warning.contains(&unignored.display().to_string()),
+            "{warning}"
+        );
+        assert!(
+            !warning.contains(&ignored.display().to_string()),
+            "{warning}"
+        );
+        assert_eq!(None, unignored_warning(&[ignored]));
+    }
+}
@@ -0,0 +1,396 @@
+use sailfish::{RenderError, TemplateOnce};
+
+use crate::{error::Error, template_dir::TemplateDir};
+
+/// The files generated for each day in one language.
+pub struct Scaffold {
+    /// Directory holding a folder per year of day folders, e.g. `rs` for
+    /// `rs/y2023/day_05`.
+    pub dir: &'static str,
+    /// Where the instructions go, relative to the day's folder.
+    pub readme: &'static str,
+    pub files: &'static [ScaffoldFile],
+}
+
+pub struct ScaffoldFile {
+    /// Path of the template, relative to the template directory.
+    pub source: &'static str,
+    /// Where the file goes, relative to the day's folder. `{package_name}` and
+    /// `{crate_name}` are replaced with the day's package and crate names.
+    pub path: &'static str,
+    /// Renders the built-in template.
+    render: fn(&Vars) -> Result<String, RenderError>,
+}
+
+impl ScaffoldFile {
+    pub fn path(&self, vars: &Vars) -> String {
+        self.path
+            .replace("{package_name}", &vars.package_name)
+            .replace("{crate_name}", &vars.crate_name)
+    }
+
+    /// Renders the file from `template_dir` if given, otherwise from the
+    /// built-in template.
+    pub fn render(&self, vars: &Vars, template_dir: Option<&TemplateDir>) -> Result<String, Error> {
+        let mut output = match template_dir {
+            Some(dir) => dir.render(self.source, &vars.pairs())?,
+            None => (self.render)(vars).map_err(|err| Error::Render {
+                template: self.source,
+                err,
+            })?,
+        };
+        output.push('\n');
+        Ok(output)
+    }
+}
+
+/// Values a day's templates are rendered with.
+#[derive(Debug, Clone, Default)]
+pub struct Vars {
+    pub crate_name: String,
+    pub package_name: String,
+    /// `Day 05: Title`, or just `Day 05` without a title.
+    pub display_name: String,
+    /// The display name, as a Rust string literal.
+    pub display_name_literal: String,
+    /// One-line description of the crate, as a TOML string.
+    pub description: String,
+    pub day: u32,
+    pub year: u32,
+    pub title: String,
+    /// The part 1 example input, as a Rust string literal.
+    pub example_input: String,
+    /// The part 1 example answer, as a Rust expression.
+    pub part_1_expected: String,
+}
+
+impl Vars {
+    /// Variables a custom template directory may use.
+    pub const NAMES: [&'static str; 10] = [
+        "crate_name",
+        "package_name",
+        "display_name",
+        "display_name_literal",
+        "description",
+        "day",
+        "year",
+        "title",
+        "example_input",
+        "part_1_expected",
+    ];
+
+    fn pairs(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("crate_name", self.crate_name.clone()),
+            ("package_name", self.package_name.clone()),
+            ("display_name", self.display_name.clone()),
+            ("display_name_literal", self.display_name_literal.clone()),
+            ("description", self.description.clone()),
+            ("day", self.day.to_string()),
+            ("year", self.year.to_string()),
+            ("title", self.title.clone()),
+            ("example_input", self.example_input.clone()),
+            ("part_1_expected", self.part_1_expected.clone()),
+        ]
+    }
+}
+
+pub const GO: Scaffold = Scaffold {
+    dir: "go",
+    readme: "README.md",
+    files: &[
+        ScaffoldFile {
+            source: "go/main.stpl.go",
+            path: "main.go",
+            render: |vars| MainGo::from(vars).render_once(),
+        },
+        ScaffoldFile {
+            source: "go/main_test.stpl.go",
+            path: "main_test.go",
+            render: |_| MainTestGo {}.render_once(),
+        },
+    ],
+};
+
+pub const PY: Scaffold = Scaffold {
+    dir: "py",
+    readme: "README.md",
+    files: &[
+        ScaffoldFile {
+            source: "py/day.stpl.py",
+            path: "{package_name}.py",
+            render: |vars| DayPy::from(vars).render_once(),
+        },
+        ScaffoldFile {
+            source: "py/test_day.stpl.py",
+            path: "test_{package_name}.py",
+            render: |vars| TestDayPy::from(vars).render_once(),
+        },
+    ],
+};
+
+pub const RS: Scaffold = Scaffold {
+    dir: "rs",
+    readme: "README.md",
+    files: &[
+        ScaffoldFile {
+            source: "rs/Cargo.stpl.toml",
+            path: "Cargo.toml",
+            render: |vars| CargoRs::from(vars).render_once(),
+        },
+        ScaffoldFile {
+            source: "rs/src/lib.stpl.rs",
+            path: "src/lib.rs",
+            render: |vars| LibRs::from(vars).render_once(),
+        },
+        ScaffoldFile {
+            source: "rs/src/bin/main.stpl.rs",
+            path: "src/bin/{crate_name}.rs",
+            render: |vars| MainRs::from(vars).render_once(),
+        },
+        ScaffoldFile {
+            source: "rs/benches/bench.stpl.rs",
+            path: "benches/bench.rs",
+            render: |vars| BenchRs::from(vars).render_once(),
+        },
+    ],
+};
+
+pub const TS: Scaffold = Scaffold {
+    dir: "ts",
+    readme: "README.md",
+    files: &[
+        ScaffoldFile {
+            source: "ts/main.stpl.ts",
+            path: "main.ts",
+            render: |_| MainTs {}.render_once(),
+        },
+        ScaffoldFile {
+            source: "ts/day.stpl.ts",
+            path: "day.ts",
+            render: |vars| DayTs::from(vars).render_once(),
+        },
+        ScaffoldFile {
+            source: "ts/day.test.stpl.ts",
+            path: "day.test.ts",
+            render: |_| DayTestTs {}.render_once(),
+        },
+        ScaffoldFile {
+            source: "ts/day.bench.stpl.ts",
+            path: "day.bench.ts",
+            render: |_| DayBenchTs {}.render_once(),
+        },
+    ],
+};
+
+pub const ZIG: Scaffold = Scaffold {
+    dir: "zig",
+    readme: "README.md",
+    files: &[ScaffoldFile {
+        source: "zig/main.stpl.zig",
+        path: "main.zig",
+        render: |vars| MainZig::from(vars).render_once(),
+    }],
+};
+
+#[derive(TemplateOnce)]
+#[template(path = "go/main.stpl.go")]
+struct MainGo<'a> {
+    package_name: &'a str,
+    year: u32,
+}
+
+impl<'a> From<&'a Vars> for MainGo<'a> {
+    fn from(vars: &'a Vars) -> Self {
+        Self {
+            package_name: &vars.package_name,
+            year: vars.year,
+        }
+    }
+}
+
+#[derive(TemplateOnce)]
+#[template(path = "go/main_test.stpl.go")]
+struct MainTestGo {}
+
+#[derive(TemplateOnce)]
+#[template(path = "py/day.stpl.py")]
+struct DayPy<'a> {
+    package_name: &'a str,
+    year: u32,
+}
+
+impl<'a> From<&'a Vars> for DayPy<'a> {
+    fn from(vars: &'a Vars) -> Self {
+        Self {
+            package_name: &vars.package_name,
+            year: vars.year,
+        }
+    }
+}
+
+#[derive(TemplateOnce)]
+#[template(path = "py/test_day.stpl.py")]
+struct TestDayPy<'a> {
+    package_name: &'a str,
+}
+
+impl<'a> From<&'a Vars> for TestDayPy<'a> {
+    fn from(vars: &'a Vars) -> Self {
+        Self {
+            package_name: &vars.package_name,
+        }
+    }
+}
+
+#[derive(TemplateOnce)]
+#[template(path = "rs/Cargo.stpl.toml")]
+struct CargoRs<'a> {
+    crate_name: &'a str,
+    description: &'a str,
+}
+
+impl<'a> From<&'a Vars> for CargoRs<'a> {
+    fn from(vars: &'a Vars) -> Self {
+        Self {
+            crate_name: &vars.crate_name,
+            description: &vars.description,
+        }
+    }
+}
+
+#[derive(TemplateOnce)]
+#[template(path = "rs/src/lib.stpl.rs")]
+struct LibRs<'a> {
+    package_name: &'a str,
+    year: u32,
+    example_input: &'a str,
+    part_1_expected: &'a str,
+}
+
+impl<'a> From<&'a Vars> for LibRs<'a> {
+    fn from(vars: &'a Vars) -> Self {
+        Self {
+            package_name: &vars.package_name,
+            year: vars.year,
+            example_input: &vars.example_input,
+            part_1_expected: &vars.part_1_expected,
+        }
+    }
+}
+
+#[derive(TemplateOnce)]
+#[template(path = "rs/src/bin/main.stpl.rs")]
+struct MainRs<'a> {
+    crate_name: &'a str,
+}
+
+impl<'a> From<&'a Vars> for MainRs<'a> {
+    fn from(vars: &'a Vars) -> Self {
+        Self {
+            crate_name: &vars.crate_name,
+        }
+    }
+}
+
+#[derive(TemplateOnce)]
+#[template(path = "rs/benches/bench.stpl.rs")]
+struct BenchRs<'a> {
+    crate_name: &'a str,
+    display_name_literal: &'a str,
+}
+
+impl<'a> From<&'a Vars> for BenchRs<'a> {
+    fn from(vars: &'a Vars) -> Self {
+        Self {
+            crate_name: &vars.crate_name,
+            display_name_literal: &vars.display_name_literal,
+        }
+    }
+}
+
+#[derive(TemplateOnce)]
+#[template(path = "ts/main.stpl.ts")]
+struct MainTs {}
+
+#[derive(TemplateOnce)]
+#[template(path = "ts/day.stpl.ts")]
+struct DayTs<'a> {
+    package_name: &'a str,
+    year: u32,
+}
+
+impl<'a> From<&'a Vars> for DayTs<'a> {
+    fn from(vars: &'a Vars) -> Self {
+        Self {
+            package_name: &vars.package_name,
+            year: vars.year,
+        }
+    }
+}
+
+#[derive(TemplateOnce)]
+#[template(path = "ts/day.bench.stpl.ts")]
+struct DayBenchTs {}
+
+#[derive(TemplateOnce)]
+#[template(path = "ts/day.test.stpl.ts")]
+struct DayTestTs {}
+
+#[derive(TemplateOnce)]
+#[template(path = "zig/main.stpl.zig")]
+struct MainZig<'a> {
+    package_name: &'a str,
+    year: u32,
+}
+
+impl<'a> From<&'a Vars> for MainZig<'a> {
+    fn from(vars: &'a Vars) -> Self {
+        Self {
+            package_name: &vars.package_name,
+            year: vars.year,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The built-in templates render the same at runtime as through sailfish.
+    #[test]
+    fn runtime_matches_built_in() {
+        let dir = TemplateDir::new(concat!(env!("CARGO_MANIFEST_DIR"), "/templates"));
+        let vars = Vars {
+            crate_name: "y2023_day_05".to_string(),
+            package_name: "day_05".to_string(),
+            display_name: "Day 05: If You Give A Seed A Fertilizer".to_string(),
+            display_name_literal: "\"Day 05: If You Give A Seed A Fertilizer\"".to_string(),
+            description: "\"Advent of Code 2023, day 5: If You Give A Seed A Fertilizer\""
+                .to_string(),
+            day: 5,
+            year: 2023,
+            title: "If You Give A Seed A Fertilizer".to_string(),
+            example_input: "\"1\n2\"".to_string(),
+            part_1_expected: "3".to_string(),
+        };
+
+        for scaffold in [GO, PY, RS, TS, ZIG] {
+            let sources = scaffold
+                .files
+                .iter()
+                .map(|file| file.source)
+                .collect::<Vec<_>>();
+            dir.validate(&sources, &Vars::NAMES).unwrap();
+
+            for file in scaffold.files {
+                assert_eq!(
+                    file.render(&vars, None).unwrap(),
+                    file.render(&vars, Some(&dir)).unwrap(),
+                    "{}",
+                    file.source
+                );
+            }
+        }
+    }
+}
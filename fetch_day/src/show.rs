@@ -0,0 +1,414 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+
+use crate::html::{self, Element, Node};
+
+/// Where fetched instruction pages are kept, so they can be shown offline.
+pub const CACHE_DIR: &str = ".cache/instructions";
+
+#[derive(Args, Debug)]
+pub struct ShowOpts {
+    #[arg(
+        short,
+        long,
+        value_parser = clap::value_parser!(u8).range(1..=2),
+        help = "Only show this part of the puzzle"
+    )]
+    pub part: Option<u8>,
+
+    #[arg(
+        long,
+        help = "Column to wrap the text at [default: the terminal's width, or 80]"
+    )]
+    pub width: Option<usize>,
+}
+
+impl ShowOpts {
+    /// `--width`, else the width of the terminal stdout is attached to.
+    pub fn width(&self) -> usize {
+        self.width
+            .or_else(|| {
+                let (terminal_size::Width(width), _) = terminal_size::terminal_size()?;
+                Some(usize::from(width))
+            })
+            .unwrap_or(DEFAULT_WIDTH)
+    }
+}
+
+/// Width to wrap at when stdout isn't a terminal.
+const DEFAULT_WIDTH: usize = 80;
+
+/// The cached instructions page for a day, e.g. `.cache/instructions/2023/day_05.html`.
+pub fn cached_path(year: u32, day: u32) -> PathBuf {
+    Path::new(CACHE_DIR)
+        .join(year.to_string())
+        .join(format!("day_{day:02}.html"))
+}
+
+/// Renders the instructions in `page_html` for the terminal, wrapped at
+/// `width`, or `None` if the page doesn't hold `part`.
+///
+/// Without `ansi`, the text is laid out the same but unstyled, for output
+/// which isn't a terminal.
+pub fn render(page_html: &str, part: Option<u8>, width: usize, ansi: bool) -> Option<String> {
+    let tree = html::parse(page_html);
+    let articles = html::find_all(&tree, "article");
+    let articles = match part {
+        Some(part) => vec![*articles.get(usize::from(part) - 1)?],
+        None if articles.is_empty() => return None,
+        None => articles,
+    };
+
+    let terminal = Terminal {
+        width: width.max(MIN_WIDTH),
+        ansi,
+    };
+    let parts = articles
+        .iter()
+        .map(|article| terminal.blocks(&article.children, "").join("\n\n"))
+        .collect::<Vec<_>>();
+    Some(parts.join("\n\n") + "\n")
+}
+
+/// Narrower than this, wrapping does more harm than good.
+const MIN_WIDTH: usize = 20;
+
+/// Opening and closing SGR sequences for a style.
+type Style = (&'static str, &'static str);
+
+const BOLD: Style = ("\x1b[1m", "\x1b[22m");
+const DIM: Style = ("\x1b[2m", "\x1b[22m");
+const CODE: Style = ("\x1b[36m", "\x1b[39m");
+const UNDERLINE: Style = ("\x1b[4m", "\x1b[24m");
+const STRIKETHROUGH: Style = ("\x1b[9m", "\x1b[29m");
+
+struct Terminal {
+    width: usize,
+    ansi: bool,
+}
+
+impl Terminal {
+    fn styled(&self, (open, close): Style, text: &str) -> String {
+        if self.ansi {
+            format!("{open}{text}{close}")
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Block-level `nodes` as paragraphs, lists and boxes, each line starting
+    /// with `indent`.
+    fn blocks(&self, nodes: &[Node], indent: &str) -> Vec<String> {
+        let mut blocks = Vec::new();
+        let mut inline = Vec::new();
+        for node in nodes {
+            match node {
+                Node::Element(el) if is_block(el) => {
+                    self.flush(&mut inline, indent, &mut blocks);
+                    blocks.push(self.block(el, indent));
+                }
+                node if node.is_whitespace() && inline.is_empty() => {}
+                node => inline.push(node.clone()),
+            }
+        }
+        self.flush(&mut inline, indent, &mut blocks);
+        blocks
+    }
+
+    /// Wraps loose inline `nodes` between blocks as a paragraph of their own.
+    fn flush(&self, inline: &mut Vec<Node>, indent: &str, blocks: &mut Vec<String>) {
+        let text = self.inline(inline);
+        inline.clear();
+        if !text.trim().is_empty() {
+            blocks.push(self.wrap(&text, indent));
+        }
+    }
+
+    fn block(&self, el: &Element, indent: &str) -> String {
+        match el.tag.as_str() {
+            "h2" => self.wrap(&self.styled(BOLD, &self.inline(&el.children)), indent),
+            "pre" => self.boxed(el, indent),
+            "ul" | "ol" => self.list(el, indent),
+            _ => self.wrap(&self.inline(&el.children), indent),
+        }
+    }
+
+    fn list(&self, el: &Element, indent: &str) -> String {
+        let items = el
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                Node::Element(item) if item.tag == "li" => Some(item),
+                _ => None,
+            })
+            .enumerate()
+            .map(|(i, item)| {
+                let marker = if el.tag == "ol" {
+                    format!("{}. ", i + 1)
+                } else {
+                    "- ".to_string()
+                };
+                let item_indent = format!("{indent}  {}", " ".repeat(marker.len()));
+                let mut blocks = self.blocks(&item.children, &item_indent);
+                // The marker takes the place of the first line's indent
+                match blocks.first_mut() {
+                    Some(first) => {
+                        first.replace_range(..item_indent.len(), &format!("{indent}  {marker}"))
+                    }
+                    None => blocks.push(format!("{indent}  {}", marker.trim_end())),
+                }
+                blocks.join("\n")
+            });
+        items.collect::<Vec<_>>().join("\n")
+    }
+
+    /// A `<pre>` block in a dimmed box, as is rather than wrapped.
+    fn boxed(&self, el: &Element, indent: &str) -> String {
+        let text = self.preformatted(&el.children);
+        let lines = text.trim_end_matches('\n').split('\n').collect::<Vec<_>>();
+        let inner = lines
+            .iter()
+            .map(|line| visible_width(line))
+            .max()
+            .unwrap_or(0);
+
+        let mut output = Vec::new();
+        let rule = "─".repeat(inner + 2);
+        output.push(format!(
+            "{indent}{}",
+            self.styled(DIM, &format!("┌{rule}┐"))
+        ));
+        for line in lines {
+            let padding = " ".repeat(inner - visible_width(line));
+            let side = self.styled(DIM, "│");
+            output.push(format!("{indent}{side} {line}{padding} {side}"));
+        }
+        output.push(format!(
+            "{indent}{}",
+            self.styled(DIM, &format!("└{rule}┘"))
+        ));
+        output.join("\n")
+    }
+
+    /// Text inside a `<pre>`, keeping its whitespace.
+    fn preformatted(&self, nodes: &[Node]) -> String {
+        nodes
+            .iter()
+            .map(|node| match node {
+                Node::Text(text) => text.to_owned(),
+                Node::Element(el) if el.tag == "em" => {
+                    self.styled(BOLD, &self.preformatted(&el.children))
+                }
+                Node::Element(el) => self.preformatted(&el.children),
+            })
+            .collect()
+    }
+
+    /// Styled text with whitespace collapsed, and a line break for each `<br>`.
+    fn inline(&self, nodes: &[Node]) -> String {
+        nodes
+            .iter()
+            .map(|node| match node {
+                Node::Text(text) => collapse_whitespace(text),
+                Node::Element(el) => match el.tag.as_str() {
+                    "em" => self.styled(BOLD, &self.inline(&el.children)),
+                    "code" => self.styled(CODE, &self.inline(&el.children)),
+                    "a" => self.styled(UNDERLINE, &self.inline(&el.children)),
+                    "s" => self.styled(STRIKETHROUGH, &self.inline(&el.children)),
+                    "br" => "\n".to_string(),
+                    "script" | "style" => String::new(),
+                    _ => self.inline(&el.children),
+                },
+            })
+            .collect()
+    }
+
+    /// Wraps `text` at the terminal width, starting each line with `indent`.
+    fn wrap(&self, text: &str, indent: &str) -> String {
+        let available = self.width.saturating_sub(indent.len()).max(MIN_WIDTH);
+
+        let mut lines = Vec::new();
+        for hard_line in text.trim().split('\n') {
+            let mut line = String::new();
+            let mut width = 0;
+            for word in hard_line.split(' ').filter(|word| !word.is_empty()) {
+                let word_width = visible_width(word);
+                if width > 0 && width + 1 + word_width > available {
+                    lines.push(line);
+                    line = String::new();
+                    width = 0;
+                }
+                if width > 0 {
+                    line.push(' ');
+                    width += 1;
+                }
+                line.push_str(word);
+                width += word_width;
+            }
+            lines.push(line);
+        }
+
+        lines
+            .iter()
+            .map(|line| format!("{indent}{line}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Elements laid out as blocks of their own, rather than within a line.
+fn is_block(el: &Element) -> bool {
+    matches!(
+        el.tag.as_str(),
+        "h2" | "p" | "pre" | "ul" | "ol" | "div" | "article"
+    )
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                collapsed.push(' ');
+            }
+            in_space = true;
+        } else {
+            collapsed.push(c);
+            in_space = false;
+        }
+    }
+    collapsed
+}
+
+/// Columns `text` takes up, ignoring SGR escape sequences.
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod render {
+        use super::*;
+
+        struct Case {
+            page: &'static str,
+            part: Option<u8>,
+            width: usize,
+            expected: Option<&'static str>,
+        }
+
+        #[test]
+        fn whole_puzzle() {
+            run(&Case {
+                page: include_str!("../fixtures/instructions/puzzle.html"),
+                part: None,
+                width: 60,
+                expected: Some(include_str!("../fixtures/instructions/puzzle.txt")),
+            })
+        }
+
+        #[test]
+        fn part_2() {
+            run(&Case {
+                page: include_str!("../fixtures/instructions/puzzle.html"),
+                part: Some(2),
+                width: 60,
+                expected: Some(
+                    "\
+--- Part Two ---
+
+Now the Elves want to know which fountain is best.
+
+Which colour produced the most drops?
+",
+                ),
+            })
+        }
+
+        #[test]
+        fn narrow() {
+            run(&Case {
+                page: include_str!("../fixtures/instructions/puzzle.html"),
+                part: Some(2),
+                width: 24,
+                expected: Some(
+                    "\
+--- Part Two ---
+
+Now the Elves want to
+know which fountain is
+best.
+
+Which colour produced
+the most drops?
+",
+                ),
+            })
+        }
+
+        #[test]
+        fn missing_part() {
+            run(&Case {
+                page: include_str!("../fixtures/instructions/extended.html"),
+                part: Some(2),
+                width: 60,
+                expected: None,
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected.map(str::to_string),
+                render(test.page, test.part, test.width, false)
+            )
+        }
+    }
+
+    #[test]
+    fn ansi_styles() {
+        let page = "<article><p>An <em>answer</em> of <code><em>42</em></code>.</p>\
+                    <pre><code>a <em>b</em>\n</code></pre></article>";
+
+        assert_eq!(
+            Some(
+                "\
+An \x1b[1manswer\x1b[22m of \x1b[36m\x1b[1m42\x1b[22m\x1b[39m.
+
+\x1b[2m┌─────┐\x1b[22m
+\x1b[2m│\x1b[22m a \x1b[1mb\x1b[22m \x1b[2m│\x1b[22m
+\x1b[2m└─────┘\x1b[22m
+"
+                .to_string()
+            ),
+            render(page, None, 80, true)
+        );
+    }
+
+    #[test]
+    fn empty_list_items() {
+        let page = "<article><ol><li>one</li><li></li><li> </li></ol><ul><li></li></ul></article>";
+
+        assert_eq!(
+            Some("  1. one\n  2.\n  3.\n\n  -\n".to_string()),
+            render(page, None, 80, false)
+        );
+    }
+
+    #[test]
+    fn width_ignores_escapes() {
+        assert_eq!(5, visible_width("\x1b[1mhello\x1b[22m"));
+    }
+}
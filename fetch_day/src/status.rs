@@ -0,0 +1,293 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::{data, html, markdown};
+
+#[derive(Args, Debug)]
+pub struct StatusOpts {
+    #[arg(long, help = "Print the report as JSON")]
+    pub json: bool,
+}
+
+/// Where one day stands, on AoC and locally.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DayStatus {
+    pub day: u32,
+    /// Stars earned, from the calendar page.
+    pub stars: u8,
+    /// The crate folder, e.g. `rs/y2023/day_05`, exists.
+    pub has_crate: bool,
+    /// `PART_1` in the crate's `lib.rs` is no longer `0`.
+    pub part_1_answered: bool,
+    pub part_2_answered: bool,
+    pub data: bool,
+    pub encrypted_data: bool,
+    /// The crate's README includes the part 2 instructions.
+    pub readme_part_2: bool,
+    /// Hover text footnotes in the crate's README.
+    pub easter_eggs: usize,
+}
+
+/// Stars per day, read from the classes of the `/{year}` calendar page.
+pub fn calendar_stars(calendar_html: &str) -> BTreeMap<u32, u8> {
+    let tree = html::parse(calendar_html);
+
+    let mut stars = BTreeMap::new();
+    for link in html::find_all(&tree, "a") {
+        let classes = link.attr("class").unwrap_or_default();
+        let Some(day) = classes
+            .split_whitespace()
+            .find_map(|class| class.strip_prefix("calendar-day")?.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let count = classes
+            .split_whitespace()
+            .map(|class| match class {
+                "calendar-verycomplete" => 2,
+                "calendar-complete" => 1,
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0);
+        stars.insert(day, count);
+    }
+    stars
+}
+
+/// Every day of the calendar, combining `stars` with what's on disk.
+pub fn status(stars: &BTreeMap<u32, u8>, crates_dir: &Path, data_dir: &Path) -> Vec<DayStatus> {
+    (1..=25)
+        .map(|day| {
+            let name = format!("day_{day:02}");
+            let crate_dir = crates_dir.join(&name);
+            let lib_rs =
+                fs::read_to_string(crate_dir.join("src").join("lib.rs")).unwrap_or_default();
+            let readme = fs::read_to_string(crate_dir.join("README.md")).unwrap_or_default();
+            let plaintext = data_dir.join(format!("{name}.txt"));
+
+            DayStatus {
+                day,
+                stars: stars.get(&day).copied().unwrap_or(0),
+                has_crate: crate_dir.is_dir(),
+                part_1_answered: answered(&lib_rs, "PART_1"),
+                part_2_answered: answered(&lib_rs, "PART_2"),
+                data: plaintext.is_file(),
+                encrypted_data: data::encrypted_path(&plaintext).is_file(),
+                readme_part_2: readme.contains("--- Part Two ---"),
+                easter_eggs: markdown::footnotes(&readme).len(),
+            }
+        })
+        .collect()
+}
+
+/// Whether `pub const <name>` has been given a value other than `0`.
+fn answered(lib_rs: &str, name: &str) -> bool {
+    lib_rs
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("pub const ")?.strip_prefix(name))
+        .filter_map(|rest| rest.split_once('=').map(|(_, value)| value))
+        .any(|value| value.trim().trim_end_matches(';').trim() != "0")
+}
+
+pub fn format_status(rows: &[DayStatus]) -> String {
+    let mark = |present: bool| if present { "yes" } else { "-" };
+
+    let mut output = format!(
+        "{:<4} {:<5} {:<5} {:<6} {:<6} {:<9} {:<9} {:<13} {}\n",
+        "Day",
+        "Stars",
+        "Crate",
+        "Part 1",
+        "Part 2",
+        "Plaintext",
+        "Encrypted",
+        "README part 2",
+        "Easter eggs"
+    );
+    for row in rows {
+        output.push_str(&format!(
+            "{:<4} {:<5} {:<5} {:<6} {:<6} {:<9} {:<9} {:<13} {}\n",
+            row.day,
+            "*".repeat(usize::from(row.stars)),
+            mark(row.has_crate),
+            mark(row.part_1_answered),
+            mark(row.part_2_answered),
+            mark(row.data),
+            mark(row.encrypted_data),
+            mark(row.readme_part_2),
+            row.easter_eggs,
+        ));
+    }
+    let total = rows.iter().map(|row| u32::from(row.stars)).sum::<u32>();
+    output.push_str(&format!("{total} stars\n"));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn stars_from_calendar() {
+        assert_eq!(
+            BTreeMap::from([(1, 2), (2, 1), (3, 0)]),
+            calendar_stars(include_str!("../fixtures/calendar/2023.html"))
+        )
+    }
+
+    mod answered {
+        use super::*;
+
+        struct Case {
+            input: &'static str,
+            expected: (bool, bool),
+        }
+
+        #[test]
+        fn template() {
+            run(&Case {
+                input: "pub const PART_1: usize = 0;\npub const PART_2: usize = 0;\n",
+                expected: (false, false),
+            })
+        }
+
+        #[test]
+        fn part_1() {
+            run(&Case {
+                input: "pub const PART_1: usize = 54927;\npub const PART_2: usize = 0;\n",
+                expected: (true, false),
+            })
+        }
+
+        #[test]
+        fn both() {
+            run(&Case {
+                input: "pub const PART_1: u64 = 1;\npub const PART_2: &str = \"EZPZ\";\n",
+                expected: (true, true),
+            })
+        }
+
+        #[test]
+        fn missing() {
+            run(&Case {
+                input: "",
+                expected: (false, false),
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected,
+                (
+                    answered(test.input, "PART_1"),
+                    answered(test.input, "PART_2")
+                )
+            )
+        }
+    }
+
+    #[test]
+    fn status_combines_calendar_and_disk() {
+        let dir = TempDir::new("status");
+        let (crates_dir, data_dir) = (dir.join("rs"), dir.join("data"));
+        fs::create_dir_all(crates_dir.join("day_01").join("src")).unwrap();
+        fs::create_dir_all(crates_dir.join("day_02")).unwrap();
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(
+            crates_dir.join("day_01").join("src").join("lib.rs"),
+            "pub const PART_1: usize = 142;\npub const PART_2: usize = 0;\n",
+        )
+        .unwrap();
+        fs::write(
+            crates_dir.join("day_01").join("README.md"),
+            "# Day 1\n\nA joke[^1].\n\n## --- Part Two ---\n\n[^1]: Hover text.\n",
+        )
+        .unwrap();
+        fs::write(data_dir.join("day_01.txt"), "1").unwrap();
+        fs::write(data_dir.join("day_01.enc.txt"), "v1$").unwrap();
+        fs::write(data_dir.join("day_02.enc.txt"), "v1$").unwrap();
+
+        let rows = status(&BTreeMap::from([(1, 2), (2, 1)]), &crates_dir, &data_dir);
+
+        assert_eq!(25, rows.len());
+        assert_eq!(
+            vec![
+                DayStatus {
+                    day: 1,
+                    stars: 2,
+                    has_crate: true,
+                    part_1_answered: true,
+                    part_2_answered: false,
+                    data: true,
+                    encrypted_data: true,
+                    readme_part_2: true,
+                    easter_eggs: 1,
+                },
+                DayStatus {
+                    day: 2,
+                    stars: 1,
+                    has_crate: true,
+                    encrypted_data: true,
+                    ..Default::default()
+                },
+                DayStatus {
+                    day: 3,
+                    ..Default::default()
+                },
+            ],
+            rows[..3]
+        );
+    }
+
+    #[test]
+    fn format_status_table() {
+        let rows = [
+            DayStatus {
+                day: 1,
+                stars: 2,
+                has_crate: true,
+                part_1_answered: true,
+                part_2_answered: true,
+                data: true,
+                encrypted_data: true,
+                readme_part_2: true,
+                easter_eggs: 2,
+            },
+            DayStatus {
+                day: 2,
+                stars: 1,
+                has_crate: true,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            "\
+Day  Stars Crate Part 1 Part 2 Plaintext Encrypted README part 2 Easter eggs
+1    **    yes   yes    yes    yes       yes       yes           2
+2    *     yes   -      -      -         -         -             0
+3 stars
+",
+            format_status(&rows)
+        )
+    }
+
+    #[test]
+    fn json() {
+        let rows = [DayStatus {
+            day: 2,
+            stars: 1,
+            has_crate: true,
+            ..Default::default()
+        }];
+
+        assert_eq!(
+            r#"[{"day":2,"stars":1,"has_crate":true,"part_1_answered":false,"part_2_answered":false,"data":false,"encrypted_data":false,"readme_part_2":false,"easter_eggs":0}]"#,
+            serde_json::to_string(&rows).unwrap()
+        )
+    }
+}
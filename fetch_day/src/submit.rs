@@ -0,0 +1,205 @@
+use std::{fmt::Display, process::ExitCode, time::Duration};
+
+use crate::client::{AocClient, FetchError};
+use regex::Regex;
+
+#[derive(clap::Args, Debug)]
+pub struct SubmitOpts {
+    #[arg(
+        short,
+        long,
+        value_parser = clap::value_parser!(u8).range(1..=2),
+        default_value_t = 1,
+        help = "Part of the puzzle the answer is for"
+    )]
+    pub part: u8,
+
+    #[arg(help = "Answer to submit")]
+    pub answer: String,
+}
+
+/// Outcome of an answer submission, as reported by AoC.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    RateLimited(Duration),
+    AlreadySolved,
+    Unrecognised(String),
+}
+
+impl Verdict {
+    /// Parses the `<article>` of the page returned after posting an answer.
+    pub fn from_response(html: &str) -> Self {
+        let article = Regex::new(r"(?s)<article.*?>(.+?)</article>")
+            .unwrap()
+            .captures(html)
+            .map(|caps| caps[1].to_string())
+            .unwrap_or_default();
+        let text = Regex::new(r"<[^>]*>")
+            .unwrap()
+            .replace_all(&article, "")
+            .to_string();
+
+        if text.contains("That's the right answer") {
+            Verdict::Correct
+        } else if text.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if text.contains("That's not the right answer") {
+            Verdict::Incorrect
+        } else if text.contains("You gave an answer too recently") {
+            let wait = Regex::new(r"You have (?:(\d+)m)?\s*(?:(\d+)s)? left to wait")
+                .unwrap()
+                .captures(&text)
+                .map(|caps| {
+                    let mins = caps.get(1).map_or(0, |m| m.as_str().parse().unwrap_or(0));
+                    let secs = caps.get(2).map_or(0, |s| s.as_str().parse().unwrap_or(0));
+                    Duration::from_secs(mins * 60 + secs)
+                })
+                .unwrap_or_default();
+            Verdict::RateLimited(wait)
+        } else if text.contains("You don't seem to be solving the right level") {
+            Verdict::AlreadySolved
+        } else {
+            Verdict::Unrecognised(text.trim().to_string())
+        }
+    }
+
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            Verdict::Correct => 0,
+            Verdict::TooHigh => 2,
+            Verdict::TooLow => 3,
+            Verdict::Incorrect => 4,
+            Verdict::RateLimited(_) => 5,
+            Verdict::AlreadySolved => 6,
+            Verdict::Unrecognised(_) => 7,
+        })
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => f.write_str("Correct!"),
+            Verdict::TooHigh => f.write_str("Incorrect: answer is too high"),
+            Verdict::TooLow => f.write_str("Incorrect: answer is too low"),
+            Verdict::Incorrect => f.write_str("Incorrect"),
+            Verdict::RateLimited(wait) => {
+                write!(f, "Rate limited: wait {}s before retrying", wait.as_secs())
+            }
+            Verdict::AlreadySolved => f.write_str("Already solved"),
+            Verdict::Unrecognised(text) => write!(f, "Unrecognised response: {text}"),
+        }
+    }
+}
+
+pub fn submit(
+    client: &AocClient,
+    year: u32,
+    day: u32,
+    opts: &SubmitOpts,
+) -> Result<Verdict, FetchError> {
+    let url = client.url(&format!("/{year}/day/{day}/answer"));
+    let level = opts.part.to_string();
+
+    let response = client.post_form(&url, &[("level", &level), ("answer", &opts.answer)])?;
+
+    Ok(Verdict::from_response(&response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::stand_in;
+
+    mod from_response {
+        use super::*;
+
+        struct Case {
+            input: &'static str,
+            expected: Verdict,
+        }
+
+        #[test]
+        fn correct() {
+            run(&Case {
+                input: include_str!("../fixtures/answer/correct.html"),
+                expected: Verdict::Correct,
+            })
+        }
+
+        #[test]
+        fn too_high() {
+            run(&Case {
+                input: include_str!("../fixtures/answer/too_high.html"),
+                expected: Verdict::TooHigh,
+            })
+        }
+
+        #[test]
+        fn too_low() {
+            run(&Case {
+                input: include_str!("../fixtures/answer/too_low.html"),
+                expected: Verdict::TooLow,
+            })
+        }
+
+        #[test]
+        fn incorrect() {
+            run(&Case {
+                input: include_str!("../fixtures/answer/incorrect.html"),
+                expected: Verdict::Incorrect,
+            })
+        }
+
+        #[test]
+        fn rate_limited() {
+            run(&Case {
+                input: include_str!("../fixtures/answer/rate_limited.html"),
+                expected: Verdict::RateLimited(Duration::from_secs(4 * 60 + 37)),
+            })
+        }
+
+        #[test]
+        fn already_solved() {
+            run(&Case {
+                input: include_str!("../fixtures/answer/already_solved.html"),
+                expected: Verdict::AlreadySolved,
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, Verdict::from_response(test.input))
+        }
+    }
+
+    #[test]
+    fn submit_posts_answer_with_session() {
+        let (host, server) =
+            stand_in::serve_once(200, include_str!("../fixtures/answer/too_low.html"));
+        let client = AocClient::new(host, "token");
+
+        let verdict = submit(
+            &client,
+            2023,
+            5,
+            &SubmitOpts {
+                part: 2,
+                answer: "1234".to_string(),
+            },
+        )
+        .unwrap();
+        let request = server.join().unwrap();
+
+        assert_eq!(Verdict::TooLow, verdict);
+        assert_eq!("POST", request.method);
+        assert_eq!("/2023/day/5/answer", request.path);
+        assert_eq!(Some("session=token"), request.header("cookie"));
+        assert_eq!("level=2&answer=1234", request.body);
+    }
+}
@@ -0,0 +1,481 @@
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use toml_edit::Document;
+
+use crate::error::{Error, WithPath};
+
+/// Per-user settings file, in the same directory as the credentials.
+pub const USER_CONFIG: &str = "config.toml";
+
+/// A directory of templates rendered at runtime, in place of the ones
+/// embedded in the binary.
+///
+/// Templates use the same layout and names as `fetch_day/templates`, and the
+/// subset of sailfish syntax the embedded ones need: `<%= var %>` inserts a
+/// variable HTML-escaped, `<%- var %>` inserts it as is.
+#[derive(Debug, Clone)]
+pub struct TemplateDir {
+    dir: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum TemplateError {
+    Io { path: PathBuf, err: std::io::Error },
+    Missing { dir: PathBuf, names: Vec<String> },
+    Unterminated { path: PathBuf },
+    Unsupported { path: PathBuf, tag: String },
+    UnknownVariable { path: PathBuf, name: String },
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::Io { path, err } => write!(f, "reading {}: {err}", path.display()),
+            TemplateError::Missing { dir, names } => write!(
+                f,
+                "template directory {} is missing {}",
+                dir.display(),
+                names.join(", ")
+            ),
+            TemplateError::Unterminated { path } => {
+                write!(f, "{}: unterminated '<%' tag", path.display())
+            }
+            TemplateError::Unsupported { path, tag } => write!(
+                f,
+                "{}: unsupported tag '{tag}'; only '<%= var %>' and '<%- var %>' are allowed",
+                path.display()
+            ),
+            TemplateError::UnknownVariable { path, name } => {
+                write!(f, "{}: unknown variable '{name}'", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// The `template_dir` set in `[workspace.metadata.fetch_day]` of the
+/// workspace `manifest`, else in the per-user `user_config`, resolved
+/// relative to the file which sets it.
+pub fn configured(manifest: &Path, user_config: Option<&Path>) -> Result<Option<PathBuf>, Error> {
+    let sources = [
+        Some((manifest, &["workspace", "metadata", "fetch_day"][..])),
+        user_config.map(|path| (path, &[][..])),
+    ];
+    for (path, keys) in sources.into_iter().flatten() {
+        if !path.exists() {
+            continue;
+        }
+        if let Some(dir) = setting(path, keys)? {
+            return Ok(Some(dir));
+        }
+    }
+    Ok(None)
+}
+
+/// `template_dir` in the table at `keys` of the TOML file at `path`.
+fn setting(path: &Path, keys: &[&str]) -> Result<Option<PathBuf>, Error> {
+    let invalid = |reason: String| Error::Manifest {
+        path: path.to_path_buf(),
+        reason,
+    };
+    let doc = fs::read_to_string(path)
+        .with_path(path)?
+        .parse::<Document>()
+        .map_err(|err| invalid(err.to_string()))?;
+
+    let mut table = doc.as_item();
+    for key in keys {
+        match table.get(key) {
+            Some(item) => table = item,
+            None => return Ok(None),
+        }
+    }
+    let Some(value) = table.get("template_dir") else {
+        return Ok(None);
+    };
+    let dir = value.as_str().ok_or_else(|| {
+        let name = keys.iter().chain(["template_dir"].iter()).copied();
+        invalid(format!(
+            "{} is not a string",
+            name.collect::<Vec<_>>().join(".")
+        ))
+    })?;
+
+    let base = path.parent().unwrap_or(Path::new("."));
+    Ok(Some(base.join(dir)))
+}
+
+enum Part<'s> {
+    Text(&'s str),
+    Variable { name: &'s str, escape: bool },
+}
+
+impl TemplateDir {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Checks that every template in `names` exists and only uses `variables`,
+    /// so nothing is written from an incomplete directory.
+    pub fn validate(&self, names: &[&str], variables: &[&str]) -> Result<(), TemplateError> {
+        let missing = names
+            .iter()
+            .filter(|name| !self.dir.join(name).is_file())
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(TemplateError::Missing {
+                dir: self.dir.clone(),
+                names: missing,
+            });
+        }
+
+        for name in names {
+            let (path, source) = self.read(name)?;
+            for part in parse(&path, &source)? {
+                match part {
+                    Part::Variable { name, .. } if !variables.contains(&name) => {
+                        return Err(TemplateError::UnknownVariable {
+                            path,
+                            name: name.to_string(),
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the template `name`, relative to the directory.
+    pub fn render(&self, name: &str, vars: &[(&str, String)]) -> Result<String, TemplateError> {
+        let (path, source) = self.read(name)?;
+
+        let mut output = String::with_capacity(source.len());
+        for part in parse(&path, &source)? {
+            match part {
+                Part::Text(text) => output.push_str(text),
+                Part::Variable { name, escape } => {
+                    let value = vars
+                        .iter()
+                        .find(|(var, _)| *var == name)
+                        .map(|(_, value)| value)
+                        .ok_or_else(|| TemplateError::UnknownVariable {
+                            path: path.clone(),
+                            name: name.to_string(),
+                        })?;
+                    if escape {
+                        output.push_str(&escape_html(value));
+                    } else {
+                        output.push_str(value);
+                    }
+                }
+            }
+        }
+
+        // Matches sailfish, which drops the template's final newline
+        if output.ends_with('\n') {
+            output.pop();
+        }
+        Ok(output)
+    }
+
+    fn read(&self, name: &str) -> Result<(PathBuf, String), TemplateError> {
+        let path = self.dir.join(name);
+        match fs::read_to_string(&path) {
+            Ok(source) => Ok((path, source)),
+            Err(err) => Err(TemplateError::Io { path, err }),
+        }
+    }
+}
+
+fn parse<'s>(path: &Path, mut source: &'s str) -> Result<Vec<Part<'s>>, TemplateError> {
+    let mut parts = Vec::new();
+    while let Some(start) = source.find("<%") {
+        parts.push(Part::Text(&source[..start]));
+
+        let rest = &source[start..];
+        let end = rest.find("%>").ok_or_else(|| TemplateError::Unterminated {
+            path: path.to_path_buf(),
+        })?;
+        let tag = &rest[..end + 2];
+
+        let escape = match tag[2..].chars().next() {
+            Some('=') => true,
+            Some('-') => false,
+            _ => {
+                return Err(TemplateError::Unsupported {
+                    path: path.to_path_buf(),
+                    tag: tag.to_string(),
+                })
+            }
+        };
+        let name = tag[3..tag.len() - 2].trim();
+        let name = name.strip_prefix('&').unwrap_or(name).trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(TemplateError::Unsupported {
+                path: path.to_path_buf(),
+                tag: tag.to_string(),
+            });
+        }
+        parts.push(Part::Variable { name, escape });
+
+        source = &rest[end + 2..];
+    }
+    parts.push(Part::Text(source));
+    Ok(parts)
+}
+
+/// The same escaping as sailfish's `<%= %>`.
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("&quot;"),
+            '&' => escaped.push_str("&amp;"),
+            '\'' => escaped.push_str("&#039;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    /// Template directory in `dir` holding `files`.
+    fn template_dir(dir: &TempDir, files: &[(&str, &str)]) -> TemplateDir {
+        for (file, contents) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        TemplateDir::new(dir.to_path_buf())
+    }
+
+    mod render {
+        use super::*;
+
+        struct Case {
+            name: &'static str,
+            input: &'static str,
+            expected: Result<&'static str, &'static str>,
+        }
+
+        #[test]
+        fn variables() {
+            run(&Case {
+                name: "render_variables",
+                input: "name = \"<%= &crate_name %>\"\n// <%-title%>\n",
+                expected: Ok("name = \"day_05\"\n// Print & \"Play\""),
+            })
+        }
+
+        #[test]
+        fn escaped() {
+            run(&Case {
+                name: "render_escaped",
+                input: "<%= title %>",
+                expected: Ok("Print &amp; &quot;Play&quot;"),
+            })
+        }
+
+        #[test]
+        fn unknown_variable() {
+            run(&Case {
+                name: "render_unknown_variable",
+                input: "<%= crate_nmae %>",
+                expected: Err("UnknownVariable"),
+            })
+        }
+
+        #[test]
+        fn code_block() {
+            run(&Case {
+                name: "render_code_block",
+                input: "<% for x in 0..2 { %>x<% } %>",
+                expected: Err("Unsupported"),
+            })
+        }
+
+        #[test]
+        fn unterminated() {
+            run(&Case {
+                name: "render_unterminated",
+                input: "<%= crate_name",
+                expected: Err("Unterminated"),
+            })
+        }
+
+        fn run(test: &Case) {
+            let temp = TempDir::new(test.name);
+            let dir = template_dir(&temp, &[("lib.rs", test.input)]);
+            let vars = [
+                ("crate_name", "day_05".to_string()),
+                ("title", "Print & \"Play\"".to_string()),
+            ];
+
+            match (test.expected, dir.render("lib.rs", &vars)) {
+                (Ok(expected), Ok(actual)) => assert_eq!(expected, actual),
+                (Err(expected), Err(actual)) => {
+                    assert!(format!("{actual:?}").starts_with(expected), "{actual:?}")
+                }
+                (expected, actual) => panic!("expected {expected:?}, got {actual:?}"),
+            }
+        }
+    }
+
+    mod validate {
+        use super::*;
+
+        struct Case {
+            name: &'static str,
+            files: &'static [(&'static str, &'static str)],
+            expected: Result<(), &'static str>,
+        }
+
+        #[test]
+        fn complete() {
+            run(&Case {
+                name: "validate_complete",
+                files: &[("rs/lib.rs", "<%= crate_name %>"), ("rs/Cargo.toml", "")],
+                expected: Ok(()),
+            })
+        }
+
+        #[test]
+        fn missing() {
+            run(&Case {
+                name: "validate_missing",
+                files: &[("rs/lib.rs", "")],
+                expected: Err("Missing { dir: DIR, names: [\"rs/Cargo.toml\"] }"),
+            })
+        }
+
+        #[test]
+        fn unknown_variable() {
+            run(&Case {
+                name: "validate_unknown_variable",
+                files: &[("rs/lib.rs", ""), ("rs/Cargo.toml", "<%= nmae %>")],
+                expected: Err("UnknownVariable { path: DIR/rs/Cargo.toml, name: \"nmae\" }"),
+            })
+        }
+
+        fn run(test: &Case) {
+            let temp = TempDir::new(test.name);
+            let dir = template_dir(&temp, test.files);
+
+            let result = dir
+                .validate(&["rs/lib.rs", "rs/Cargo.toml"], &["crate_name"])
+                .map_err(|err| {
+                    format!("{err:?}")
+                        .replace(
+                            &format!("{:?}", dir.dir.join("rs/Cargo.toml")),
+                            "DIR/rs/Cargo.toml",
+                        )
+                        .replace(&format!("{:?}", dir.dir), "DIR")
+                });
+            assert_eq!(test.expected.map_err(str::to_string), result);
+        }
+    }
+
+    mod configured {
+        use super::*;
+
+        struct Case {
+            name: &'static str,
+            manifest: &'static str,
+            user_config: Option<&'static str>,
+            expected: Result<Option<&'static str>, &'static str>,
+        }
+
+        #[test]
+        fn workspace_metadata() {
+            run(&Case {
+                name: "configured_workspace_metadata",
+                manifest:
+                    "[workspace]\n\n[workspace.metadata.fetch_day]\ntemplate_dir = \"templates\"\n",
+                user_config: Some("template_dir = \"/home/templates\"\n"),
+                expected: Ok(Some("ROOT/templates")),
+            })
+        }
+
+        #[test]
+        fn user_config() {
+            run(&Case {
+                name: "configured_user_config",
+                manifest: "[workspace]\n",
+                user_config: Some("template_dir = \"/home/templates\"\n"),
+                expected: Ok(Some("/home/templates")),
+            })
+        }
+
+        #[test]
+        fn relative_to_user_config() {
+            run(&Case {
+                name: "configured_relative_user_config",
+                manifest: "[workspace]\n",
+                user_config: Some("template_dir = \"templates\"\n"),
+                expected: Ok(Some("CONFIG/templates")),
+            })
+        }
+
+        #[test]
+        fn unset() {
+            run(&Case {
+                name: "configured_unset",
+                manifest: "[workspace]\n\n[workspace.metadata.other]\ntemplate_dir = 1\n",
+                user_config: None,
+                expected: Ok(None),
+            })
+        }
+
+        #[test]
+        fn not_a_string() {
+            run(&Case {
+                name: "configured_not_a_string",
+                manifest: "[workspace]\n\n[workspace.metadata.fetch_day]\ntemplate_dir = 1\n",
+                user_config: None,
+                expected: Err(
+                    "ROOT/Cargo.toml: workspace.metadata.fetch_day.template_dir is not a string",
+                ),
+            })
+        }
+
+        fn run(test: &Case) {
+            let temp = TempDir::new(test.name);
+            let (root, config) = (temp.join("root"), temp.join("config"));
+            fs::create_dir_all(&root).unwrap();
+            fs::create_dir_all(&config).unwrap();
+            let manifest = root.join("Cargo.toml");
+            fs::write(&manifest, test.manifest).unwrap();
+            let user_config = config.join(USER_CONFIG);
+            if let Some(contents) = test.user_config {
+                fs::write(&user_config, contents).unwrap();
+            }
+
+            let placeholders = |text: String| {
+                text.replace(&root.display().to_string(), "ROOT")
+                    .replace(&config.display().to_string(), "CONFIG")
+            };
+            let result = configured(&manifest, Some(&user_config))
+                .map(|dir| dir.map(|dir| placeholders(dir.display().to_string())))
+                .map_err(|err| placeholders(err.to_string()));
+            assert_eq!(
+                test.expected
+                    .map(|dir| dir.map(str::to_string))
+                    .map_err(str::to_string),
+                result
+            );
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use toml_edit::{Array, Document, Item, Table, Value};
+
+use crate::{
+    error::{Error, WithPath},
+    files::FileWriter,
+};
+
+/// Specs for workspace dependencies the day templates may inherit, used when
+/// the workspace manifest doesn't declare them yet.
+const KNOWN_DEPENDENCIES: [(&str, &str); 2] = [
+    (
+        "criterion",
+        r#"{ version = "0.5.1", features = ["html_reports"] }"#,
+    ),
+    ("rayon", r#"{ version = "1.8.0" }"#),
+];
+
+/// The nearest of `start` and its ancestors whose `Cargo.toml` has a
+/// `[workspace]` table.
+pub fn find_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| {
+            fs::read_to_string(dir.join("Cargo.toml"))
+                .ok()
+                .and_then(|manifest| manifest.parse::<Document>().ok())
+                .is_some_and(|doc| doc.get("workspace").is_some_and(Item::is_table))
+        })
+        .map(Path::to_path_buf)
+}
+
+/// Adds `member` to the workspace manifest at `manifest_path`, along with
+/// any workspace dependencies its `member_manifest` inherits.
+///
+/// Running it again for the same member leaves the manifest untouched.
+pub fn register_member(
+    manifest_path: &Path,
+    member: &str,
+    member_manifest: &str,
+    files: &FileWriter,
+) -> Result<(), Error> {
+    let manifest = fs::read_to_string(manifest_path).with_path(manifest_path)?;
+
+    let updated =
+        with_member(&manifest, member, member_manifest).map_err(|reason| Error::Manifest {
+            path: manifest_path.to_path_buf(),
+            reason,
+        })?;
+
+    files.update(manifest_path, &updated)?;
+    Ok(())
+}
+
+/// The updated manifest, or why `manifest` can't take the member.
+fn with_member(manifest: &str, member: &str, member_manifest: &str) -> Result<String, String> {
+    let mut doc = manifest
+        .parse::<Document>()
+        .map_err(|err| err.to_string())?;
+    let workspace = doc
+        .get_mut("workspace")
+        .and_then(Item::as_table_mut)
+        .ok_or("manifest has no [workspace] table")?;
+
+    let default_members = workspace
+        .entry("default-members")
+        .or_insert(Item::Value(Value::Array(Array::new())))
+        .as_array_mut()
+        .ok_or("workspace.default-members is not an array")?;
+    insert_sorted(default_members, member);
+
+    let inherited = inherited_dependencies(member_manifest)
+        .map_err(|err| format!("{member}/Cargo.toml: {err}"))?;
+    for name in inherited {
+        let dependencies = workspace
+            .entry("dependencies")
+            .or_insert(Item::Table(Table::new()))
+            .as_table_mut()
+            .ok_or("workspace.dependencies is not a table")?;
+        if dependencies.contains_key(&name) {
+            continue;
+        }
+
+        let spec = KNOWN_DEPENDENCIES
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, spec)| spec)
+            .ok_or_else(|| format!("no known version for workspace dependency '{name}'"))?;
+        let spec = spec.parse::<Value>().map_err(|err| err.to_string())?;
+        dependencies.insert(&name, Item::Value(spec));
+    }
+
+    Ok(doc.to_string())
+}
+
+/// Inserts `member` in sorted position, keeping the array's layout.
+fn insert_sorted(array: &mut Array, member: &str) {
+    if array.iter().any(|value| value.as_str() == Some(member)) {
+        return;
+    }
+
+    let index = array
+        .iter()
+        .position(|value| value.as_str().is_some_and(|existing| existing > member))
+        .unwrap_or(array.len());
+
+    let multiline = array.iter().any(|value| {
+        value
+            .decor()
+            .prefix()
+            .and_then(|prefix| prefix.as_str())
+            .is_some_and(|prefix| prefix.contains('\n'))
+    });
+
+    if multiline {
+        let mut value = Value::from(member);
+        let neighbour = array.get(index.min(array.len() - 1)).unwrap();
+        *value.decor_mut() = neighbour.decor().clone();
+        array.insert_formatted(index, value);
+    } else {
+        array.insert(index, member);
+        array.fmt();
+    }
+}
+
+/// Names of the dependencies declared with `workspace = true`.
+fn inherited_dependencies(member_manifest: &str) -> Result<Vec<String>, toml_edit::TomlError> {
+    let doc = member_manifest.parse::<Document>()?;
+
+    let mut names = Vec::new();
+    for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+        let Some(table) = doc.get(section).and_then(Item::as_table_like) else {
+            continue;
+        };
+        for (name, spec) in table.iter() {
+            let inherited = spec
+                .as_table_like()
+                .and_then(|spec| spec.get("workspace"))
+                .and_then(Item::as_bool)
+                .unwrap_or(false);
+            if inherited && !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    const MEMBER_MANIFEST: &str = r#"[package]
+name = "day_05"
+
+[dependencies]
+rayon = { workspace = true }
+
+[dev-dependencies]
+criterion = { workspace = true }
+"#;
+
+    #[test]
+    fn find_root_from_member() {
+        let root = TempDir::new("find_root");
+        let member = root.join("rs").join("y2023").join("day_05");
+        fs::create_dir_all(member.join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"rs/*/*\"]\n",
+        )
+        .unwrap();
+        fs::write(member.join("Cargo.toml"), MEMBER_MANIFEST).unwrap();
+
+        assert_eq!(Some(root.to_path_buf()), find_root(&member.join("src")));
+        assert_eq!(Some(root.to_path_buf()), find_root(&root));
+        assert_eq!(None, find_root(&std::env::temp_dir()));
+    }
+
+    mod with_member {
+        use super::*;
+
+        struct Case {
+            input: &'static str,
+            expected: &'static str,
+        }
+
+        #[test]
+        fn empty_default_members() {
+            run(&Case {
+                input: r#"[workspace]
+resolver = "2"
+
+default-members = []
+members = ["fetch_day", "rs/*"]
+
+[workspace.dependencies]
+criterion = { version = "0.5.1", features = ["html_reports"] }
+rayon = { version = "1.8.0" }
+"#,
+                expected: r#"[workspace]
+resolver = "2"
+
+default-members = ["rs/day_05"]
+members = ["fetch_day", "rs/*"]
+
+[workspace.dependencies]
+criterion = { version = "0.5.1", features = ["html_reports"] }
+rayon = { version = "1.8.0" }
+"#,
+            })
+        }
+
+        #[test]
+        fn sorted_with_comments() {
+            run(&Case {
+                input: r#"# the workspace
+[workspace]
+default-members = ["rs/day_01", "rs/day_09"] # keep sorted
+members = ["rs/*"]
+
+[workspace.dependencies]
+criterion = "0.5" # pinned
+rayon = "1"
+"#,
+                expected: r#"# the workspace
+[workspace]
+default-members = ["rs/day_01", "rs/day_05", "rs/day_09"] # keep sorted
+members = ["rs/*"]
+
+[workspace.dependencies]
+criterion = "0.5" # pinned
+rayon = "1"
+"#,
+            })
+        }
+
+        #[test]
+        fn multiline_default_members() {
+            run(&Case {
+                input: r#"[workspace]
+default-members = [
+    "rs/day_01",
+    "rs/day_02",
+]
+
+[workspace.dependencies]
+criterion = "0.5"
+rayon = "1"
+"#,
+                expected: r#"[workspace]
+default-members = [
+    "rs/day_01",
+    "rs/day_02",
+    "rs/day_05",
+]
+
+[workspace.dependencies]
+criterion = "0.5"
+rayon = "1"
+"#,
+            })
+        }
+
+        #[test]
+        fn missing_dependencies() {
+            run(&Case {
+                input: r#"[workspace]
+members = ["rs/*"]
+
+[workspace.dependencies]
+rayon = "1"
+"#,
+                expected: r#"[workspace]
+members = ["rs/*"]
+default-members = ["rs/day_05"]
+
+[workspace.dependencies]
+rayon = "1"
+criterion = { version = "0.5.1", features = ["html_reports"] }
+"#,
+            })
+        }
+
+        #[test]
+        fn already_registered() {
+            run(&Case {
+                input: r#"[workspace]
+default-members = ["rs/day_05"]
+
+[workspace.dependencies]
+criterion = "0.5"
+rayon = "1"
+"#,
+                expected: r#"[workspace]
+default-members = ["rs/day_05"]
+
+[workspace.dependencies]
+criterion = "0.5"
+rayon = "1"
+"#,
+            })
+        }
+
+        fn run(test: &Case) {
+            let once = with_member(test.input, "rs/day_05", MEMBER_MANIFEST).unwrap();
+            let twice = with_member(&once, "rs/day_05", MEMBER_MANIFEST).unwrap();
+
+            assert_eq!(test.expected, once);
+            assert_eq!(once, twice);
+        }
+    }
+}
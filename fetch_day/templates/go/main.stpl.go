@@ -22,7 +22,7 @@ func main() {
 }
 
 func readData(dir string) string {
-	contents, err := os.ReadFile(filepath.Join(dir, "<%= &package_name %>.txt"))
+	contents, err := os.ReadFile(filepath.Join(dir, "<%= year %>", "<%= &package_name %>.txt"))
 	if err != nil {
 		panic(err)
 	}
@@ -5,7 +5,7 @@ import (
 	"testing"
 )
 
-var contents = readData("../../data")
+var contents = readData("../../../data")
 
 func TestParseContents(t *testing.T) {
 	tests := map[string]struct {
@@ -0,0 +1,32 @@
+from pathlib import Path
+
+PART_1_SOLUTION = 0
+PART_2_SOLUTION = 0
+
+DATA_DIR = Path(__file__).resolve().parents[3] / "data"
+
+
+class Input:
+    pass
+
+
+def read_data(data_dir: Path = DATA_DIR) -> str:
+    return (data_dir / "<%= year %>" / "<%= &package_name %>.txt").read_text().strip()
+
+
+def parse_contents(contents: str) -> Input:
+    raise NotImplementedError
+
+
+def part_1(data: Input) -> int:
+    raise NotImplementedError
+
+
+def part_2(data: Input) -> int:
+    raise NotImplementedError
+
+
+if __name__ == "__main__":
+    data = parse_contents(read_data())
+    print("Part 1:", part_1(data))
+    print("Part 2:", part_2(data))
@@ -0,0 +1,48 @@
+import pytest
+
+from <%= &package_name %> import (
+    PART_1_SOLUTION,
+    PART_2_SOLUTION,
+    Input,
+    parse_contents,
+    part_1,
+    part_2,
+    read_data,
+)
+
+
+def example_data() -> Input:
+    raise NotImplementedError
+
+
+@pytest.mark.parametrize(
+    "contents, expected",
+    [
+        pytest.param("", example_data, id="example"),
+    ],
+)
+def test_parse_contents(contents, expected):
+    assert parse_contents(contents) == expected()
+
+
+@pytest.mark.parametrize(
+    "data, expected",
+    [
+        pytest.param(example_data, 0, id="example"),
+        pytest.param(lambda: parse_contents(read_data()), PART_1_SOLUTION, id="actual"),
+    ],
+)
+def test_part_1(data, expected):
+    assert part_1(data()) == expected
+
+
+@pytest.mark.parametrize(
+    "data, expected",
+    [
+        pytest.param(example_data, 0, id="example"),
+        pytest.param(lambda: parse_contents(read_data()), PART_2_SOLUTION, id="actual"),
+    ],
+)
+def test_part_2(data, expected):
+    assert part_2(data()) == expected
+
@@ -10,11 +10,11 @@ fn main() {
 fn bench() {
     let mut criterion = Criterion::default().configure_from_args();
 
-    let dir = "../../data";
+    let dir = "../../../data";
     let contents = read_data(dir);
     let input = Input::from_data(&contents);
 
-    let mut group = criterion.benchmark_group("<%= &display_name %>");
+    let mut group = criterion.benchmark_group(<%- display_name_literal %>);
 
     group.bench_with_input("parse contents", &contents, |b, i| {
         b.iter(|| Input::from_data(i))
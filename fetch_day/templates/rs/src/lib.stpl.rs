@@ -4,7 +4,7 @@ pub const PART_1: usize = 0;
 pub const PART_2: usize = 0;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("<%= &crate_name %>.txt"))
+    read_to_string(Path::new(data_dir).join("<%= year %>").join("<%= &package_name %>.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -31,7 +31,7 @@ impl Input {
 mod tests {
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
@@ -66,7 +66,7 @@ mod tests {
         fn example() {
             run(&Case {
                 data: super::example().1,
-                expected: todo!(),
+                expected: <%- part_1_expected %>,
             })
         }
 
@@ -113,6 +113,6 @@ mod tests {
     }
 
     fn example() -> (&'static str, Input) {
-        ("", Input {})
+        (<%- example_input %>, Input {})
     }
 }
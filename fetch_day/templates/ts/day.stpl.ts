@@ -4,7 +4,7 @@ export const part2Solution = 0;
 export type Input = {};
 
 export function readData(): string {
-  return Deno.readTextFileSync("./data/<%= &package_name %>.txt")
+  return Deno.readTextFileSync("./data/<%= year %>/<%= &package_name %>.txt")
     .trim();
 }
 
@@ -0,0 +1,80 @@
+const std = @import("std");
+
+const part1_solution: usize = 0;
+const part2_solution: usize = 0;
+
+const Input = struct {
+    fn parse(allocator: std.mem.Allocator, contents: []const u8) !Input {
+        _ = allocator;
+        _ = contents;
+        @panic("todo");
+    }
+
+    fn part1(self: Input) usize {
+        _ = self;
+        @panic("todo");
+    }
+
+    fn part2(self: Input) usize {
+        _ = self;
+        @panic("todo");
+    }
+};
+
+pub fn main() !void {
+    var arena = std.heap.ArenaAllocator.init(std.heap.page_allocator);
+    defer arena.deinit();
+    const allocator = arena.allocator();
+
+    const contents = try readData(allocator, "./data");
+    const input = try Input.parse(allocator, contents);
+
+    const stdout = std.io.getStdOut().writer();
+    try stdout.print("Part 1: {}\n", .{input.part1()});
+    try stdout.print("Part 2: {}\n", .{input.part2()});
+}
+
+fn readData(allocator: std.mem.Allocator, dir: []const u8) ![]const u8 {
+    const path = try std.fs.path.join(allocator, &.{ dir, "<%= year %>", "<%= &package_name %>.txt" });
+    const contents = try std.fs.cwd().readFileAlloc(allocator, path, std.math.maxInt(usize));
+    return std.mem.trim(u8, contents, &std.ascii.whitespace);
+}
+
+const example = "";
+
+fn exampleData() Input {
+    @panic("todo");
+}
+
+test "parse contents: example" {
+    var arena = std.heap.ArenaAllocator.init(std.testing.allocator);
+    defer arena.deinit();
+
+    try std.testing.expectEqualDeep(exampleData(), try Input.parse(arena.allocator(), example));
+}
+
+test "part 1: example" {
+    try std.testing.expectEqual(@as(usize, 0), exampleData().part1());
+}
+
+test "part 1: actual" {
+    var arena = std.heap.ArenaAllocator.init(std.testing.allocator);
+    defer arena.deinit();
+    const allocator = arena.allocator();
+
+    const input = try Input.parse(allocator, try readData(allocator, "./data"));
+    try std.testing.expectEqual(part1_solution, input.part1());
+}
+
+test "part 2: example" {
+    try std.testing.expectEqual(@as(usize, 0), exampleData().part2());
+}
+
+test "part 2: actual" {
+    var arena = std.heap.ArenaAllocator.init(std.testing.allocator);
+    defer arena.deinit();
+    const allocator = arena.allocator();
+
+    const input = try Input.parse(allocator, try readData(allocator, "./data"));
+    try std.testing.expectEqual(part2_solution, input.part2());
+}
@@ -0,0 +1,279 @@
+//! Runs fetch_day end to end against `aoc_stand_in`, serving `fixtures`.
+
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    path::Path,
+    process::{Child, Command, Output, Stdio},
+};
+
+#[path = "../src/test_util.rs"]
+mod test_util;
+
+use test_util::TempDir;
+
+const SESSION: &str = "stand-in-session";
+
+/// A running `aoc_stand_in`, stopped when dropped.
+struct StandIn {
+    process: Child,
+    host: String,
+}
+
+impl StandIn {
+    fn start(args: &[&str]) -> Self {
+        let mut process = Command::new(env!("CARGO_BIN_EXE_aoc_stand_in"))
+            .args(["--session", SESSION, "--unlocked-through", "3"])
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let mut line = String::new();
+        BufReader::new(process.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let host = line
+            .trim()
+            .strip_prefix("Listening on ")
+            .unwrap_or_else(|| panic!("unexpected first line: {line:?}"))
+            .to_string();
+
+        Self { process, host }
+    }
+
+    /// Runs fetch_day against the stand-in, from a fresh workspace at `root`.
+    fn fetch_day(&self, root: &Path, session: &str, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_fetch_day"))
+            .current_dir(root)
+            .env("XDG_CONFIG_HOME", root.join("config"))
+            .env("AOC_SESSION_TOKEN", session)
+            .env("AOC_AES_KEY", "stand-in-key")
+            .env_remove("AOC_WORKSPACE_ROOT")
+            .env_remove("AOC_TEMPLATE_DIR")
+            .env_remove("AOC_CONTACT")
+            .args(["--base-url", &self.host, "--request-interval", "0"])
+            .args(["--year", "2023"])
+            .args(args)
+            .output()
+            .unwrap()
+    }
+}
+
+impl Drop for StandIn {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+fn workspace(name: &str) -> TempDir {
+    let root = TempDir::new(name);
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nresolver = \"2\"\nmembers = []\n",
+    )
+    .unwrap();
+    root
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn fetches_a_day() {
+    let stand_in = StandIn::start(&[]);
+    let root = workspace("stand_in_fetch");
+
+    let output = stand_in.fetch_day(&root, SESSION, &["--day", "1", "--langs", "rs"]);
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!("Day 01: fetched\n", stdout(&output));
+    assert_eq!(
+        "7\n11\n24\n",
+        fs::read_to_string(root.join("data/2023/day_01.txt")).unwrap()
+    );
+    assert!(root.join("data/2023/day_01.enc.txt").exists());
+    let readme = fs::read_to_string(root.join("rs/y2023/day_01/README.md")).unwrap();
+    assert!(
+        readme.starts_with("---\ntitle: \"Stand-in Sums\"\nyear: 2023\nday: 1\n"),
+        "{readme}"
+    );
+    assert!(
+        readme.contains("\nparts: [1]\n---\n\n# [--- Day 1"),
+        "{readme}"
+    );
+    let crate_manifest = fs::read_to_string(root.join("rs/y2023/day_01/Cargo.toml")).unwrap();
+    assert!(
+        crate_manifest.contains("description = \"Advent of Code 2023, day 1: Stand-in Sums\""),
+        "{crate_manifest}"
+    );
+    let bench = fs::read_to_string(root.join("rs/y2023/day_01/benches/bench.rs")).unwrap();
+    assert!(
+        bench.contains("benchmark_group(\"Day 01: Stand-in Sums\")"),
+        "{bench}"
+    );
+    let lib_rs = fs::read_to_string(root.join("rs/y2023/day_01/src/lib.rs")).unwrap();
+    assert!(lib_rs.contains("expected: 12,"), "{lib_rs}");
+    let manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+    assert!(manifest.contains("\"rs/y2023/day_01\""), "{manifest}");
+}
+
+#[test]
+fn locked_day() {
+    let stand_in = StandIn::start(&[]);
+    let root = workspace("stand_in_locked");
+
+    let output = stand_in.fetch_day(&root, SESSION, &["--day", "4", "--langs", "rs"]);
+
+    assert_eq!(Some(13), output.status.code(), "{output:?}");
+    assert!(!root.join("data/2023/day_04.txt").exists());
+}
+
+#[test]
+fn wrong_session() {
+    let stand_in = StandIn::start(&[]);
+    let root = workspace("stand_in_session");
+
+    let output = stand_in.fetch_day(&root, "expired", &["--day", "1", "--langs", "rs"]);
+
+    assert_eq!(Some(12), output.status.code(), "{output:?}");
+    let check = stand_in.fetch_day(&root, "expired", &["check"]);
+    assert_eq!(Some(12), check.status.code(), "{check:?}");
+}
+
+#[test]
+fn rate_limited() {
+    let stand_in = StandIn::start(&["--min-interval", "60"]);
+    let root = workspace("stand_in_rate_limited");
+
+    let output = stand_in.fetch_day(&root, SESSION, &["--day", "1", "--langs", "rs"]);
+
+    assert_eq!(Some(11), output.status.code(), "{output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("429"),
+        "{output:?}"
+    );
+}
+
+#[test]
+fn submit() {
+    let stand_in = StandIn::start(&["--answer-cooldown", "0"]);
+    let root = workspace("stand_in_submit");
+    let submit = |answer| stand_in.fetch_day(&root, SESSION, &["submit", "--day", "1", answer]);
+
+    for (answer, code, verdict) in [
+        ("50", 2, "Incorrect: answer is too high\n"),
+        ("41", 3, "Incorrect: answer is too low\n"),
+        ("42", 0, "Correct!\n"),
+        ("42", 6, "Already solved\n"),
+    ] {
+        let output = submit(answer);
+        assert_eq!(Some(code), output.status.code(), "{output:?}");
+        assert_eq!(verdict, stdout(&output));
+    }
+}
+
+#[test]
+fn check() {
+    let stand_in = StandIn::start(&[]);
+    let root = workspace("stand_in_check");
+
+    let output = stand_in.fetch_day(&root, SESSION, &["check"]);
+
+    assert!(output.status.success(), "{output:?}");
+    assert!(stdout(&output).starts_with("Logged in as "), "{output:?}");
+}
+
+#[test]
+fn leaderboard() {
+    let stand_in = StandIn::start(&[]);
+    let root = workspace("stand_in_leaderboard");
+
+    let output = stand_in.fetch_day(&root, SESSION, &["leaderboard", "--id", "1", "--day", "1"]);
+
+    assert!(output.status.success(), "{output:?}");
+    assert!(stdout(&output).contains("alice"), "{output:?}");
+    assert!(stdout(&output).contains("\nDay 1\n"), "{output:?}");
+
+    let table = stand_in.fetch_day(&root, SESSION, &["leaderboard", "--id", "1"]);
+    assert!(table.status.success(), "{table:?}");
+    assert!(stdout(&table).contains("alice"), "{table:?}");
+    assert!(!stdout(&table).contains("\nDay 1\n"), "{table:?}");
+
+    let days = stand_in.fetch_day(
+        &root,
+        SESSION,
+        &["leaderboard", "--id", "1", "--day", "1..=3"],
+    );
+    assert_eq!(Some(10), days.status.code(), "{days:?}");
+}
+
+#[test]
+fn encrypted_readmes() {
+    let stand_in = StandIn::start(&[]);
+    let root = workspace("stand_in_readmes");
+    let readme = root.join("rs/y2023/day_01/README.md");
+
+    let output = stand_in.fetch_day(
+        &root,
+        SESSION,
+        &["--day", "1", "--langs", "rs", "--encrypt-readmes"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let contents = fs::read_to_string(&readme).unwrap();
+    let encrypted = fs::read_to_string(root.join("rs/y2023/day_01/README.enc.md")).unwrap();
+    assert!(!encrypted.contains("Stand-in Sums"), "{encrypted}");
+
+    fs::remove_file(&readme).unwrap();
+    let output = stand_in.fetch_day(&root, SESSION, &["--decrypt-data", "--day", "2"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(!readme.exists());
+    let output = stand_in.fetch_day(&root, SESSION, &["--decrypt-data"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(contents, fs::read_to_string(&readme).unwrap());
+}
+
+#[test]
+fn show_offline() {
+    let mut stand_in = StandIn::start(&[]);
+    let root = workspace("stand_in_show");
+    let output = stand_in.fetch_day(&root, SESSION, &["--day", "1", "--langs", "rs"]);
+    assert!(output.status.success(), "{output:?}");
+
+    stand_in.process.kill().unwrap();
+    stand_in.process.wait().unwrap();
+    let output = stand_in.fetch_day(&root, SESSION, &["show", "--day", "1", "--width", "40"]);
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        "\
+--- Day 1: Stand-in Sums ---
+
+The Elves are rehearsing with a stand-in
+calendar until the real one is ready.
+
+Each line of the rehearsal log (your
+puzzle input) is the number of parcels
+wrapped in one hour. For example:
+
+┌───┐
+│ 3 │
+│ 4 │
+│ 5 │
+└───┘
+
+In this example, 12 parcels were
+wrapped.
+
+How many parcels were wrapped in total?
+",
+        stdout(&output)
+    );
+    let part_2 = stand_in.fetch_day(&root, SESSION, &["show", "--day", "1", "--part", "2"]);
+    assert_eq!(Some(10), part_2.status.code(), "{part_2:?}");
+    let uncached = stand_in.fetch_day(&root, SESSION, &["show", "--day", "2"]);
+    assert_eq!(Some(10), uncached.status.code(), "{uncached:?}");
+}
@@ -4,7 +4,7 @@ pub const PART_1: usize = 54927;
 pub const PART_2: usize = 54581;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_01.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_01.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -90,7 +90,7 @@ impl<'i> Input<'i> {
 mod tests {
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
@@ -4,7 +4,7 @@ pub const PART_1: usize = 1867;
 pub const PART_2: usize = 84538;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_02.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_02.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -105,7 +105,7 @@ mod tests {
 
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
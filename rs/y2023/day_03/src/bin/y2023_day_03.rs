@@ -1,4 +1,4 @@
-use day_01::{read_data, Input};
+use y2023_day_03::{read_data, Input};
 
 fn main() {
     let contents = read_data("./data");
@@ -4,7 +4,7 @@ pub const PART_1: usize = 527369;
 pub const PART_2: usize = 73074886;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_03.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_03.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -145,7 +145,7 @@ enum Entry {
 mod tests {
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
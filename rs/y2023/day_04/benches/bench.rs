@@ -1,5 +1,5 @@
 use criterion::Criterion;
-use day_04::{read_data, Input, PART_1, PART_2};
+use y2023_day_04::{read_data, Input, PART_1, PART_2};
 
 fn main() {
     bench();
@@ -10,7 +10,7 @@ fn main() {
 fn bench() {
     let mut criterion = Criterion::default().configure_from_args();
 
-    let dir = "../../data";
+    let dir = "../../../data";
     let contents = read_data(dir);
     let input = Input::from_data(&contents);
 
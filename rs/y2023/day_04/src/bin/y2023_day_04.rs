@@ -1,4 +1,4 @@
-use day_02::{read_data, Input};
+use y2023_day_04::{read_data, Input};
 
 fn main() {
     let contents = read_data("./data");
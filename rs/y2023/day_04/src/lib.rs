@@ -4,7 +4,7 @@ pub const PART_1: usize = 27454;
 pub const PART_2: usize = 6857330;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_04.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_04.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -87,7 +87,7 @@ mod tests {
 
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
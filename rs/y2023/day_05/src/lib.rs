@@ -4,7 +4,7 @@ pub const PART_1: usize = 389056265;
 pub const PART_2: usize = 137516820;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_05.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_05.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -266,7 +266,7 @@ impl Input {
 mod tests {
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
@@ -4,7 +4,7 @@ pub const PART_1: usize = 219849;
 pub const PART_2: usize = 29432455;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_06.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_06.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -103,7 +103,7 @@ pub struct Race {
 mod tests {
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
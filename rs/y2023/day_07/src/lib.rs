@@ -4,7 +4,7 @@ pub const PART_1: usize = 248559379;
 pub const PART_2: usize = 249631254;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_07.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_07.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -232,7 +232,7 @@ enum HandType {
 mod tests {
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
@@ -4,7 +4,7 @@ pub const PART_1: usize = 12361;
 pub const PART_2: usize = 18215611419223;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_08.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_08.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -127,7 +127,7 @@ mod tests {
 
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
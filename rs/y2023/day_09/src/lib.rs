@@ -4,7 +4,7 @@ pub const PART_1: usize = 1789635132;
 pub const PART_2: usize = 913;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_09.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_09.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -76,7 +76,7 @@ fn prev(sequence: &[isize]) -> isize {
 mod tests {
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
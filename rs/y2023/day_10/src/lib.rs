@@ -8,7 +8,7 @@ pub const PART_1: usize = 7063;
 pub const PART_2: usize = 589;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_10.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_10.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -221,7 +221,7 @@ enum Pipe {
 mod tests {
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;
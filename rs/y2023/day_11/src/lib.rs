@@ -6,7 +6,7 @@ pub const PART_1: usize = 10173804;
 pub const PART_2: usize = 634324905172;
 
 pub fn read_data(data_dir: &str) -> String {
-    read_to_string(Path::new(data_dir).join("day_11.txt"))
+    read_to_string(Path::new(data_dir).join("2023").join("day_11.txt"))
         .unwrap()
         .trim()
         .to_string()
@@ -100,7 +100,7 @@ impl Input {
 mod tests {
     use super::*;
 
-    const DATA_DIR: &str = "../../data";
+    const DATA_DIR: &str = "../../../data";
 
     mod from_data {
         use super::*;